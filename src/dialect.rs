@@ -0,0 +1,160 @@
+//! Markup dialects (see `Dialect`).
+
+use crate::render::{RenderOnce, Url};
+use crate::tag::{URL_ATTRIBUTES, VOID_ELEMENTS};
+use crate::template::{Escaping, TemplateBuffer};
+
+/// The rules for serializing a markup language: which elements are void, how attributes without
+/// values are written, and how text is escaped.
+///
+/// `html!`, `xml!` and `xhtml!` use the built-in `Html`, `Xml` and `Xhtml` dialects. Custom
+/// dialects are used with `markup!` (or `owned_markup!`). Every method has a default matching
+/// `Html` so a dialect only needs to override what it changes:
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// use horrorshow::dialect::{Dialect, Html};
+/// use horrorshow::Template;
+///
+/// /// HTML with a few obsolete void elements.
+/// struct LegacyHtml;
+///
+/// impl Dialect for LegacyHtml {
+///     fn is_void(tag: &str) -> bool {
+///         Html::is_void(tag) || tag.eq_ignore_ascii_case("keygen") || tag.eq_ignore_ascii_case("frame")
+///     }
+/// }
+///
+/// let page = markup!(LegacyHtml; frameset { frame(src = "a.html"); frame(src = "b.html"); });
+/// assert_eq!(
+///     page.into_string().unwrap(),
+///     "<frameset><frame src=\"a.html\"><frame src=\"b.html\"></frameset>"
+/// );
+/// # }
+/// ```
+///
+/// Unlike the built-in dialects (which resolve all of this at compile time), custom dialects are
+/// consulted at runtime.
+pub trait Dialect {
+    /// The escaping used for text.
+    const ESCAPING: Escaping = Escaping::Html;
+
+    /// The escaping used for attribute values.
+    const ATTRIBUTE_ESCAPING: Escaping = Escaping::HtmlAttribute;
+
+    /// If true, attributes without values repeat their name (`checked="checked"`). Otherwise, they
+    /// are written bare (`checked`).
+    const BOOLEAN_ATTRIBUTE_VALUES: bool = false;
+
+    /// Returns true if `tag` is a void element: an element that never has an end tag.
+    fn is_void(tag: &str) -> bool {
+        VOID_ELEMENTS.iter().any(|v| v.eq_ignore_ascii_case(tag))
+    }
+
+    /// Returns the string that ends the start tag of an element without children when that
+    /// element has no end tag (`>` or `/>`), or `None` if it needs an end tag.
+    fn empty_tag_end(tag: &str) -> Option<&'static str> {
+        if Self::is_void(tag) {
+            Some(">")
+        } else {
+            None
+        }
+    }
+
+    /// Returns the escaping used for the contents of `tag`, if it differs from `ESCAPING`.
+    fn content_escaping(tag: &str) -> Option<Escaping> {
+        if tag.eq_ignore_ascii_case("script") {
+            Some(Escaping::Script)
+        } else if tag.eq_ignore_ascii_case("style") {
+            Some(Escaping::Style)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the attribute `name` contains a URL (and should be rendered through `Url`).
+    fn is_url_attribute(name: &str) -> bool {
+        URL_ATTRIBUTES.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+/// HTML (`html!`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Html;
+
+impl Dialect for Html {}
+
+/// XML (`xml!`): there are no void elements, empty elements are closed with `/>`, and all
+/// attributes have values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Xml;
+
+impl Dialect for Xml {
+    const ESCAPING: Escaping = Escaping::Xml;
+    const ATTRIBUTE_ESCAPING: Escaping = Escaping::Xml;
+    const BOOLEAN_ATTRIBUTE_VALUES: bool = true;
+
+    fn is_void(_tag: &str) -> bool {
+        false
+    }
+
+    fn empty_tag_end(_tag: &str) -> Option<&'static str> {
+        Some("/>")
+    }
+
+    fn content_escaping(_tag: &str) -> Option<Escaping> {
+        None
+    }
+
+    fn is_url_attribute(_name: &str) -> bool {
+        false
+    }
+}
+
+/// XHTML (`xhtml!`): HTML elements serialized as well-formed XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Xhtml;
+
+impl Dialect for Xhtml {
+    const ESCAPING: Escaping = Escaping::Xml;
+    const ATTRIBUTE_ESCAPING: Escaping = Escaping::Xml;
+    const BOOLEAN_ATTRIBUTE_VALUES: bool = true;
+
+    fn empty_tag_end(tag: &str) -> Option<&'static str> {
+        if Self::is_void(tag) {
+            Some("/>")
+        } else {
+            None
+        }
+    }
+
+    fn content_escaping(_tag: &str) -> Option<Escaping> {
+        None
+    }
+}
+
+/// Used by `markup!` to end a start tag without children. `end_tag` is `"></tag>"`.
+#[doc(hidden)]
+pub fn empty_tag_end<D: Dialect>(tag: &str, end_tag: &'static str) -> &'static str {
+    D::empty_tag_end(tag).unwrap_or(end_tag)
+}
+
+/// Used by the template macros to render attribute values.
+#[doc(hidden)]
+#[inline]
+pub fn render_attribute_value<D: Dialect, V: RenderOnce>(
+    tmpl: &mut TemplateBuffer<'_>,
+    name: &str,
+    value: V,
+) {
+    let escaping = tmpl.set_escaping(D::ATTRIBUTE_ESCAPING);
+    tmpl.start_attribute_value(name);
+    if D::is_url_attribute(name) {
+        Url(value).render_once(tmpl);
+    } else {
+        value.render_once(tmpl);
+    }
+    tmpl.end_attribute_value();
+    tmpl.set_escaping(escaping);
+}
@@ -1,7 +1,9 @@
 use core::fmt;
 
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
 #[cfg(feature = "alloc")]
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
 #[cfg(feature = "std")]
 use std::io;
@@ -16,13 +18,9 @@ pub struct Error {
     pub write: Option<io::Error>,
     /// The error(s) emitted by the template itself.
     ///
-    /// FEATURE:
-    ///
-    /// * When built without "std" but with "alloc", this is a `Vec<String>`.
-    /// * When built without "std" and without "alloc", this is an
-    ///   `Option<&'static str>`. If multiple errors are emitted, only the first
-    ///   is stored.
-    pub render: Vec<Box<dyn std::error::Error + Send + Sync>>,
+    /// FEATURE: When built without "alloc", this is an `Option<&'static str>`. If multiple errors
+    /// are emitted, only the first is stored.
+    pub render: Vec<RenderError>,
 }
 
 #[cfg(not(feature = "std"))]
@@ -30,11 +28,73 @@ pub struct Error {
 pub struct Error {
     pub write: Option<fmt::Error>,
     #[cfg(feature = "alloc")]
-    pub render: Vec<alloc::string::String>,
+    pub render: Vec<RenderError>,
     #[cfg(not(feature = "alloc"))]
     pub render: Option<&'static str>,
 }
 
+/// Where a template was defined (the location of the `html!` invocation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// An error recorded by a template (see `TemplateBuffer::record_error`), along with where it was
+/// recorded.
+///
+/// `Display` only shows the error itself. Use the alternate form (`{:#}`) to include the path and
+/// the location.
+///
+/// FEATURE: requires "alloc". When built without "std", `source` is a `String`.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct RenderError {
+    /// The elements that were open when the error was recorded, outermost first (e.g.,
+    /// `["html", "body", "div"]`).
+    pub path: Vec<String>,
+    /// The innermost template being rendered when the error was recorded (if known).
+    pub location: Option<Location>,
+    /// The error itself.
+    #[cfg(feature = "std")]
+    pub source: Box<dyn std::error::Error + Send + Sync>,
+    #[cfg(not(feature = "std"))]
+    pub source: String,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+        if f.alternate() {
+            if !self.path.is_empty() {
+                write!(f, " (at {}", self.path.join(" > "))?;
+            } else {
+                write!(f, " (at top level")?;
+            }
+            if let Some(location) = self.location {
+                write!(f, ", in template at {}", location)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RenderError {
+    // `Display` already shows `source` so skip straight to its cause.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.source()
+    }
+}
+
 #[inline]
 #[cfg(feature = "alloc")]
 pub(crate) fn is_empty(e: &Error) -> bool {
@@ -47,10 +107,44 @@ pub(crate) fn is_empty(e: &Error) -> bool {
     e.write.is_none() && e.render.is_none()
 }
 
+impl Error {
+    /// Returns true if writing the output failed.
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        self.write.is_some()
+    }
+
+    /// The errors recorded by the template itself.
+    ///
+    /// FEATURE: requires "alloc".
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn render_errors(&self) -> &[RenderError] {
+        &self.render
+    }
+
+    /// Iterate over all the underlying errors: the write error (if any) followed by the render
+    /// errors.
+    ///
+    /// FEATURE: requires "std".
+    #[cfg(feature = "std")]
+    pub fn causes(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        self.write
+            .iter()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+            .chain(
+                self.render
+                    .iter()
+                    .map(|e| e as &(dyn std::error::Error + 'static)),
+            )
+    }
+}
+
 #[cfg(feature = "std")]
 impl ::std::error::Error for Error {
-    fn description(&self) -> &'static str {
-        "Template rendering failed"
+    /// The write error if writing failed, otherwise the first render error.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.causes().next()
     }
 }
 
@@ -64,27 +158,37 @@ impl From<io::Error> for Error {
     }
 }
 
+/// Summarizes the errors (e.g., `2 render errors`). The errors themselves are the `source` and
+/// the `causes` of this error (so error reporters don't show them twice); use the alternate form
+/// (`{:#}`) to list them along with their paths and locations.
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut displayed = false;
         if let Some(e) = self.write.as_ref() {
             displayed = true;
-            write!(f, "write error: {}", e)?
+            write!(f, "write error")?;
+            if f.alternate() {
+                write!(f, ": {}", e)?;
+            }
         }
         #[cfg(feature = "alloc")]
         {
-            let mut errs = self.render.iter().fuse();
-            if let Some(err) = errs.next() {
+            if !self.render.is_empty() {
                 if displayed {
                     write!(f, "; ")?;
                 } else {
                     displayed = true;
                 }
-                write!(f, "render errors: {}", err)?;
-                for err in errs {
-                    write!(f, ", {}", err)?;
+                if self.render.len() == 1 {
+                    write!(f, "render error")?;
+                } else {
+                    write!(f, "{} render errors", self.render.len())?;
+                }
+                if f.alternate() {
+                    for (i, err) in self.render.iter().enumerate() {
+                        write!(f, "{}{:#}", if i == 0 { ": " } else { ", " }, err)?;
+                    }
                 }
-                write!(f, "; ")?;
             }
         }
         #[cfg(not(feature = "alloc"))]
@@ -95,7 +199,10 @@ impl fmt::Display for Error {
                 } else {
                     displayed = true;
                 }
-                write!(f, "render error: {}", e)?;
+                write!(f, "render error")?;
+                if f.alternate() {
+                    write!(f, ": {}", e)?;
+                }
             }
         }
         if !displayed {
@@ -25,3 +25,8 @@ pub mod doctype {
            Frameset "<!DOCTYPE HTML PUBLIC \"-//W3C//DTD HTML 4.01 Frameset//EN\" \"http://www.w3.org/TR/html4/frameset.dtd\">");
     }
 }
+
+/// Helpers for inserting XML declarations.
+pub mod declaration {
+    c!("The XML 1.0 declaration (UTF-8)." => XML "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+}
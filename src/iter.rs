@@ -0,0 +1,100 @@
+use core::iter::Peekable;
+
+/// Information about the current iteration of a loop (see `WithLoop`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Loop {
+    index: usize,
+    last: bool,
+    length: Option<usize>,
+}
+
+impl Loop {
+    /// The (zero based) index of the current item.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// True if this is the first item.
+    #[inline]
+    pub fn first(&self) -> bool {
+        self.index == 0
+    }
+
+    /// True if this is the last item.
+    #[inline]
+    pub fn last(&self) -> bool {
+        self.last
+    }
+
+    /// The total number of items, if the iterator knew it up front.
+    #[inline]
+    pub fn length(&self) -> Option<usize> {
+        self.length
+    }
+}
+
+/// Iterator returned by `WithLoop::with_loop`.
+pub struct LoopIter<I: Iterator> {
+    iter: Peekable<I>,
+    index: usize,
+    length: Option<usize>,
+}
+
+impl<I: Iterator> Iterator for LoopIter<I> {
+    type Item = (I::Item, Loop);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let info = Loop {
+            index: self.index,
+            last: self.iter.peek().is_none(),
+            length: self.length,
+        };
+        self.index += 1;
+        Some((item, info))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for LoopIter<I> {}
+
+/// Pair each item with its loop metadata (index, first, last, and length).
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// use horrorshow::prelude::*;
+///
+/// let items = ["a", "b", "c"];
+/// let page = html! {
+///     @ for (item, info) in items.iter().with_loop() {
+///         li(class = if info.last() { "last" } else if info.index() % 2 == 0 { "even" } else { "odd" }) : item;
+///     }
+/// };
+/// assert_eq!(
+///     page.into_string().unwrap(),
+///     "<li class=\"even\">a</li><li class=\"odd\">b</li><li class=\"last\">c</li>"
+/// );
+/// # }
+/// ```
+pub trait WithLoop: IntoIterator + Sized {
+    /// Pair each item with its loop metadata.
+    fn with_loop(self) -> LoopIter<Self::IntoIter> {
+        let iter = self.into_iter();
+        let length = match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        LoopIter {
+            iter: iter.peekable(),
+            index: 0,
+            length: length,
+        }
+    }
+}
+
+impl<I: IntoIterator> WithLoop for I {}
@@ -0,0 +1,263 @@
+//! A tiny JSON serializer for embedding data in pages (see `Json`).
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Something that can be serialized as JSON.
+///
+/// Implementations must write a single, complete JSON value. Strings should be written with
+/// `write_json_str` which escapes everything needed to safely embed the result in HTML (including
+/// inside `<script>` elements).
+pub trait ToJson {
+    /// Write this value as JSON.
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+/// Write a JSON string literal.
+///
+/// In addition to the escapes required by JSON, this escapes `<`, `>`, `&`, U+2028 and U+2029 so
+/// the output can't close a `<script>` element, open an HTML comment, or break javascript parsers
+/// that predate ES2019.
+pub fn write_json_str(w: &mut dyn fmt::Write, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' | '\\' | '<' | '>' | '&' | '\u{2028}' | '\u{2029}' | '\x00'..='\x1f' => (),
+            _ => continue,
+        }
+        w.write_str(&s[start..i])?;
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            _ => write!(w, "\\u{:04x}", c as u32)?,
+        }
+        start = i + c.len_utf8();
+    }
+    w.write_str(&s[start..])?;
+    w.write_char('"')
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        (**self).write_json(w)
+    }
+}
+
+impl ToJson for () {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        w.write_str("null")
+    }
+}
+
+impl ToJson for bool {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        w.write_str(if *self { "true" } else { "false" })
+    }
+}
+
+impl ToJson for str {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_json_str(w, self)
+    }
+}
+
+impl ToJson for char {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_json_str(w, self.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Some(v) => v.write_json(w),
+            None => w.write_str("null"),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        w.write_char('[')?;
+        for (i, v) in self.iter().enumerate() {
+            if i > 0 {
+                w.write_char(',')?;
+            }
+            v.write_json(w)?;
+        }
+        w.write_char(']')
+    }
+}
+
+macro_rules! impl_int_json {
+    ($($t:ty),+) => {
+        $(
+            impl ToJson for $t {
+                fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+                    write!(w, "{}", self)
+                }
+            }
+        )+
+    }
+}
+
+impl_int_json!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_float_json {
+    ($($t:ty),+) => {
+        $(
+            // JSON has no representation for NaN or infinity. Very large and very small numbers use
+            // exponent notation (like JavaScript) instead of spelling out every digit.
+            impl ToJson for $t {
+                fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+                    // `abs` isn't available without std.
+                    let abs = if self.is_sign_negative() { -*self } else { *self };
+                    if !self.is_finite() {
+                        w.write_str("null")
+                    } else if abs != 0.0 && !(1e-6..1e21).contains(&abs) {
+                        write!(w, "{:e}", self)
+                    } else {
+                        write!(w, "{}", self)
+                    }
+                }
+            }
+        )+
+    }
+}
+
+impl_float_json!(f32, f64);
+
+#[cfg(feature = "alloc")]
+impl ToJson for String {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_json_str(w, self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        (**self).write_json(w)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ToJson> ToJson for Vec<T> {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self[..].write_json(w)
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn write_json_object<'a, K, V, I>(w: &mut dyn fmt::Write, entries: I) -> fmt::Result
+where
+    K: AsRef<str> + 'a,
+    V: ToJson + 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    w.write_char('{')?;
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            w.write_char(',')?;
+        }
+        write_json_str(w, k.as_ref())?;
+        w.write_char(':')?;
+        v.write_json(w)?;
+    }
+    w.write_char('}')
+}
+
+#[cfg(feature = "alloc")]
+impl<K: AsRef<str>, V: ToJson> ToJson for BTreeMap<K, V> {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_json_object(w, self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: AsRef<str>, V: ToJson, S> ToJson for HashMap<K, V, S> {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_json_object(w, self)
+    }
+}
+
+/// A JSON value, for when your data doesn't already implement `ToJson`.
+///
+/// Object keys are written in insertion order and are not deduplicated.
+///
+/// FEATURE: requires "alloc".
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[cfg(feature = "alloc")]
+impl ToJson for JsonValue {
+    fn write_json(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            JsonValue::Null => w.write_str("null"),
+            JsonValue::Bool(v) => v.write_json(w),
+            JsonValue::Number(v) => v.write_json(w),
+            JsonValue::String(v) => v.write_json(w),
+            JsonValue::Array(v) => v.write_json(w),
+            JsonValue::Object(entries) => write_json_object(w, entries.iter().map(|(k, v)| (k, v))),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<bool> for JsonValue {
+    fn from(v: bool) -> Self {
+        JsonValue::Bool(v)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<f64> for JsonValue {
+    fn from(v: f64) -> Self {
+        JsonValue::Number(v)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<&'a str> for JsonValue {
+    fn from(v: &'a str) -> Self {
+        JsonValue::String(v.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for JsonValue {
+    fn from(v: String) -> Self {
+        JsonValue::String(v)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(JsonValue::Null, Into::into)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Into<JsonValue>> From<Vec<T>> for JsonValue {
+    fn from(v: Vec<T>) -> Self {
+        JsonValue::Array(v.into_iter().map(Into::into).collect())
+    }
+}
@@ -94,8 +94,8 @@
 //! * `some_tag;` -- Insert a the tag `some_tag`.
 //!
 //! * `some_tag(attr=rust_expresion,...);` -- Insert a the tag `some_tag` with the specified
-//!    attributes. The attribute values will be evaluated as rust expressions at runtime and they
-//!    must implement `RenderOnce` (already implemented on &str, String, other templates, etc.).
+//!   attributes. The attribute values will be evaluated as rust expressions at runtime and they
+//!   must implement `RenderOnce` (already implemented on &str, String, other templates, etc.).
 //!
 //! * `some_tag(attr,...);` -- You can also omit the value.
 //!
@@ -103,19 +103,47 @@
 //!
 //! * `some_tag(attr? = some_boolean,...);` -- You can optionally include an attribute without a value.
 //!
-//! * `some_tag { ... }` -- Insert the tag `some_tag` and recursively evaluate the `...`.
+//! * `some_tag(..attrs, attr=...);` -- Insert a set of attributes built at runtime (see `Attrs`).
+//!
+//! * `some_tag { ... }` -- Insert the tag `some_tag` and recursively evaluate the `...`. In `html!` and
+//!   `xhtml!`, void elements (`br`, `img`, etc.) can't have children.
 //!
 //! * `some_tag(...) { ... }` -- Same as above but with custom attributes.
 //!
+//! * `"some-tag"("@attr"="value", ...) { ... }` -- Tag and attribute names can also be written as
+//!   string literals (for names that aren't valid rust identifiers). Names that would break the
+//!   markup are rejected at compile time (the error mentions `INVALID_TAG_OR_ATTRIBUTE_NAME`).
+//!
+//! * `@ tag(rust_expression) { ... }`, `@ tag(rust_expression)(...) { ... }`,
+//!   `@ tag(rust_expression);` -- Insert a tag whose name is computed at runtime (e.g., `h1`..`h6`).
+//!   Invalid tag names and children on void elements are reported as render errors.
+//!
 //! * `: rust_expression`, `: { rust_code }` -- Evaluate the expression or block and insert result
-//! current position. To insert a literal html (unescaped), mark it as raw with the `Raw` marker type.
+//!   current position. To insert a literal html (unescaped), mark it as raw with the `Raw` marker type.
+//!
+//! * `@ comment : rust_expression;` -- Insert a comment (see `Comment`).
+//!
+//! * `@ cdata : rust_expression;`, `@ pi(target) : rust_expression;` -- Insert a CDATA section or
+//!   a processing instruction (see `CData` and `ProcessingInstruction`).
 //!
 //! * `|tmpl| rust_expression`, `|tmpl| { rust_code }` -- Evaluate the expression or block. This is
-//! actually a closure so the block/expression can append to the current template through `tmpl`
-//! (of type `&mut TemplateBuffer`).
+//!   actually a closure so the block/expression can append to the current template through `tmpl`
+//!   (of type `&mut TemplateBuffer`).
 //!
 //! * `@ for ...`, `@ while ...`, `@ if ...` -- you can embed basic control flow expressions.
 //!
+//! * `@ for x in iter { ... } else { ... }` -- the `else` block is rendered when `iter` is empty.
+//!
+//! * `@ for (x, info) in iter.with_loop() { ... }` -- `WithLoop` exposes the index of the current
+//!   item, whether it's the first or last, and (if known) the total number of items.
+//!
+//! * `@ match expr { Pattern => { ... }, Pattern if guard => { ... } }` -- match on an
+//!   expression; each arm's body is a template.
+//!
+//! * `@ let pattern = rust_expression;` -- Introduce a binding that can be used by the rest of the
+//!   enclosing block (but not outside of it). Type annotations are supported on simple bindings
+//!   (`@ let name: Type = ...;`).
+//!
 //! ## Traits, traits oh-my!
 //!
 //! You will likely notice that there are four render traits:
@@ -139,40 +167,87 @@
 //! `TemplateBuffer::record_error`. As with IO errors, custom errors DO NOT cause rendering to be
 //! aborted. Instead, all recorded errors (if any) are returned when rendering completes.
 //!
-//! TL;DR: There is no way to abort rendering but you can report errors.
+//! Each recorded error is a `RenderError` which also records the elements that were open (its
+//! `path`) and the template (`html!` invocation) that was being rendered (its `location`).
+//!
+//! To stop at the first error instead (e.g., to avoid running expensive expressions after a
+//! failure), wrap the template in `FailFast`. To render a placeholder instead of failing the
+//! whole page when part of it fails, wrap that part in an `ErrorBoundary`.
+//!
+//! TL;DR: Rendering isn't aborted unless you ask for it (`FailFast`) but you can report errors.
 //!
 //! ## Escaping
 //!
-//! This library does HTML escaping by default. However, it doesn't do any javascript/URL escaping.
-//! Furthermore, it will do html escaping in any literal javascript you might include.
+//! This library does HTML escaping by default.
+//!
+//! The values of attributes that contain URLs (`href`, `src`, `action`, etc.) are rendered through
+//! `Url`, which percent-encodes characters that aren't valid in URLs and records an error instead
+//! of rendering URLs with unsafe schemes (e.g., `javascript:`).
+//!
+//! Inside `script` and `style` elements, `html!` switches to javascript/CSS-safe escaping (see
+//! `Escaping`): text is inserted as-is except that `</` and `<!` are broken up with a backslash so
+//! that interpolated values can't close the element.
 //!
 //! For example, the following will display an alert:
 //!
 //! ```
 //! # #[macro_use]
 //! # extern crate horrorshow;
+//! # use horrorshow::Template;
 //! # fn main() {
-//! html! {
+//! let page = html! {
 //!   script {
-//!     : "alert('hello');"
+//!     : "alert(\"hello\");"
 //!   }
-//! }
-//! # ;
+//! };
+//! assert_eq!(page.into_string().unwrap(), "<script>alert(\"hello\");</script>");
 //! # }
 //! ```
 //!
-//! The following will break due to html escaping (the `"` will be escaped to `&quot;`):
+//! And the following can't escape from the script:
 //!
 //! ```
 //! # #[macro_use]
 //! # extern crate horrorshow;
+//! # use horrorshow::Template;
 //! # fn main() {
-//! html! {
+//! let name = "</script><script>alert('pwned');";
+//! let page = html! {
 //!   script {
-//!     : "alert(\"hello\");"
+//!     : "var name = '";
+//!     : name;
+//!     : "';"
 //!   }
-//! }
-//! # ;
+//! };
+//! assert_eq!(
+//!     page.into_string().unwrap(),
+//!     "<script>var name = '<\\/script><script>alert('pwned');';</script>"
+//! );
+//! # }
+//! ```
+//!
+//! However, that doesn't make arbitrary values safe to interpolate into javascript code (above,
+//! `name` could still contain a `'` and end the string literal early). To embed a value in a
+//! script, render it with `Json`: it writes a complete javascript value (strings are quoted) with
+//! everything that could end the string or the element escaped:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate horrorshow;
+//! # use horrorshow::{Json, Template};
+//! # fn main() {
+//! let name = "'; alert('pwned'); '</script>";
+//! let page = html! {
+//!   script {
+//!     : "var name = ";
+//!     : Json(name);
+//!     : ";"
+//!   }
+//! };
+//! assert_eq!(
+//!     page.into_string().unwrap(),
+//!     "<script>var name = \"'; alert('pwned'); '\\u003c/script\\u003e\";</script>"
+//! );
 //! # }
 //! ```
 //!
@@ -300,26 +375,54 @@ doc_comment::doctest!("../README.md");
 
 #[macro_use]
 mod macros;
+mod strict;
 
 #[cfg(feature = "ops")]
 mod ops;
 
 mod error;
-pub use crate::error::Error;
+#[cfg(feature = "alloc")]
+pub use crate::error::RenderError;
+pub use crate::error::{Error, Location};
 
 mod template;
-pub use crate::template::{Template, TemplateBuffer};
+pub use crate::template::{Escaping, Template, TemplateBuffer};
 mod render;
+mod url;
 
+mod tag;
+#[doc(hidden)]
+pub use crate::tag::{is_valid_attribute_name, is_valid_tag_name, DynamicTag};
+
+pub mod dialect;
+pub use crate::dialect::Dialect;
+
+mod iter;
+pub use crate::iter::{Loop, LoopIter, WithLoop};
+
+#[cfg(feature = "alloc")]
+mod namespaces;
 #[cfg(feature = "alloc")]
-pub use crate::render::RenderBox;
-pub use crate::render::{Concat, Escape, FnRenderer, Join, Raw, Render, RenderMut, RenderOnce};
+mod nesting;
+#[cfg(feature = "alloc")]
+mod parse;
+#[cfg(feature = "alloc")]
+mod pretty;
+
+#[cfg(feature = "alloc")]
+pub use crate::render::{Attrs, CheckNamespaces, CheckNesting, ErrorBoundary, RenderBox};
+pub use crate::render::{
+    CData, Comment, Concat, Escape, FailFast, FnRenderer, Join, Json, ProcessingInstruction, Raw,
+    Render, RenderMut, RenderOnce, Url,
+};
 
 /// Traits that should always be imported.
 pub mod prelude;
 
 pub mod helper;
 
+pub mod json;
+
 /// Helper trait for dispatching `attr ?= `.
 ///
 /// attr ?= Some("test") -> attr="test"
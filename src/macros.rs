@@ -2,13 +2,40 @@
 #![cfg_attr(rustfmt, rustfmt_skip)]
 
 /// Create a new HTML template.
+///
+/// Void elements can't have children:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// html! { br { : "x" } };
+/// # }
+/// ```
+///
+/// Tag and attribute names written as string literals must be valid:
+///
+/// ```compile_fail,E0080
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// html! { "my tag" {} };
+/// # }
+/// ```
+///
+/// ```compile_fail,E0080
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// html! { div("a=\"b\"" = "c") {} };
+/// # }
+/// ```
 #[macro_export]
 macro_rules! html {
     ($($inner:tt)*) => {{
         // Define this up here to prevent rust from saying:
         // Hey look, it's an FnOnce (this could be Fn/FnMut).
         let f = |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
             $crate::append_html!(__tmpl, html, (), $($inner)*);
+            __tmpl.leave_template(__location);
         };
         // Stringify the template content to get a hint at how much we should allocate...
         $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
@@ -20,7 +47,30 @@ macro_rules! html {
 macro_rules! xml {
     ($($inner:tt)*) => {{
         let f = |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$crate::dialect::Xml as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
             $crate::append_html!(__tmpl, xml, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
+        };
+        $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
+    }}
+}
+
+/// Create a new XHTML template.
+///
+/// Like `html!` but the output is also well-formed XML: void elements are closed with `/>`
+/// (`<br/>`), attributes always have values (`checked="checked"`), and all text (including the
+/// contents of `script` and `style` elements) is escaped as XML.
+#[macro_export]
+macro_rules! xhtml {
+    ($($inner:tt)*) => {{
+        let f = |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$crate::dialect::Xhtml as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
+            $crate::append_html!(__tmpl, xhtml, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
         };
         $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
     }}
@@ -33,7 +83,9 @@ macro_rules! owned_html {
         // Define this up here to prevent rust from saying:
         // Hey look, it's an FnOnce (this could be Fn/FnMut).
         let f = move |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
             $crate::append_html!(__tmpl, html, (), $($inner)*);
+            __tmpl.leave_template(__location);
         };
         // Stringify the template content to get a hint at how much we should allocate...
         $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
@@ -45,7 +97,58 @@ macro_rules! owned_html {
 macro_rules! owned_xml {
     ($($inner:tt)*) => {{
         let f = move |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$crate::dialect::Xml as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
             $crate::append_html!(__tmpl, xml, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
+        };
+        $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
+    }}
+}
+
+/// Create a new XHTML template taking ownership of any variables used inside.
+#[macro_export]
+macro_rules! owned_xhtml {
+    ($($inner:tt)*) => {{
+        let f = move |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$crate::dialect::Xhtml as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
+            $crate::append_html!(__tmpl, xhtml, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
+        };
+        $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
+    }}
+}
+
+/// Create a new template in a custom dialect (see `Dialect`).
+///
+/// The dialect must be named by a single identifier (import it first): `markup!(MyDialect; ...)`.
+#[macro_export]
+macro_rules! markup {
+    ($d:ident; $($inner:tt)*) => {{
+        let f = |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$d as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
+            $crate::append_html!(__tmpl, $d, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
+        };
+        $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
+    }}
+}
+
+/// Create a new template in a custom dialect taking ownership of any variables used inside.
+#[macro_export]
+macro_rules! owned_markup {
+    ($d:ident; $($inner:tt)*) => {{
+        let f = move |__tmpl: &mut $crate::TemplateBuffer| -> () {
+            let __escaping = __tmpl.set_escaping(<$d as $crate::Dialect>::ESCAPING);
+            let __location = __tmpl.enter_template($crate::Location { file: file!(), line: line!() });
+            $crate::append_html!(__tmpl, $d, (), $($inner)*);
+            __tmpl.leave_template(__location);
+            __tmpl.set_escaping(__escaping);
         };
         $crate::FnRenderer::with_capacity(stringify!($($inner)*).len(), f)
     }}
@@ -100,39 +203,116 @@ macro_rules! box_html {
 macro_rules! append_html {
 
     // Nop out close-tags for void elements.
-    (@close_tag html area) => {">"};
-    (@close_tag html base) => {">"};
-    (@close_tag html br) => {">"};
-    (@close_tag html col) => {">"};
-    (@close_tag html embed) => {">"};
-    (@close_tag html hr) => {">"};
-    (@close_tag html img) => {">"};
-    (@close_tag html input) => {">"};
-    (@close_tag html link) => {">"};
-    (@close_tag html meta) => {">"};
-    (@close_tag html param) => {">"};
-    (@close_tag html source) => {">"};
-    (@close_tag html track) => {">"};
-    (@close_tag html wbr) => {">"};
-    (@close_tag html $($tag:ident)-+) => {
-        concat!("></", $crate::append_html!(@stringify_compressed $($tag)-+), ">");
-    };
-    (@close_tag xml $($tag:ident)-+) => {
-        "/>"
+    (@close_tag html $($tag:tt)+) => { $crate::__void_tag!(">", $($tag)+) };
+    (@close_tag xhtml $($tag:tt)+) => { $crate::__void_tag!("/>", $($tag)+) };
+    (@close_tag xml $($tag:tt)+) => { "/>" };
+    // Custom dialects decide at runtime.
+    (@close_tag $d:ident $($tag:ident)-+) => {
+        $crate::dialect::empty_tag_end::<$d>(
+            $crate::append_html!(@stringify_compressed $($tag)-+),
+            concat!("></", $crate::append_html!(@stringify_compressed $($tag)-+), ">"),
+        )
+    };
+    (@close_tag $d:ident $tag:tt) => {
+        $crate::dialect::empty_tag_end::<$d>($tag, concat!("></", $tag, ">"))
+    };
+    // Reject string literal names that would break the markup at compile time (by indexing out of
+    // bounds in a constant named after the problem, rustc names it in the error).
+    (@check_name $check:ident $name:literal) => {
+        const _: () = {
+            const INVALID_TAG_OR_ATTRIBUTE_NAME: () = [()][!$crate::$check($name) as usize];
+            INVALID_TAG_OR_ATTRIBUTE_NAME
+        };
+    };
+    (@check_name $check:ident $name:tt) => {
+        compile_error!(concat!("unexpected token tree: ", stringify!($name), "\n\nYou're probably missing a semicolon somewhere."));
     };
     (@stringify_compressed $($tok:tt)*) => {
         concat!($(stringify!($tok)),*)
     };
 
+    // Switch escaping modes inside elements whose content isn't parsed as HTML.
+    (@escaping $type:ident $($tag:ident)-+) => {
+        <$crate::append_html!(@dialect $type) as $crate::Dialect>::content_escaping(
+            $crate::append_html!(@stringify_compressed $($tag)-+))
+    };
+    (@escaping $type:ident $tag:tt) => {
+        <$crate::append_html!(@dialect $type) as $crate::Dialect>::content_escaping($tag)
+    };
+
     (@block_identity $b:block) => { $b };
+
+    // Stop rendering after the first error inside `FailFast`. We never return early: elements,
+    // templates and escaping modes still need to be closed/restored.
+    (@abort_check $tmpl:ident) => {
+        if $tmpl.is_aborted() {
+            break;
+        }
+    };
+    (@unless_aborted $tmpl:ident, {$($body:tt)*}) => {
+        if !$tmpl.is_aborted() {
+            $($body)*
+        }
+    };
+
+    // Void elements can't have children (XML doesn't have void elements and custom dialects are
+    // only known at runtime).
+    (@check_void html ($($tag:tt)+), ($($children:tt)*)) => { $crate::append_html!(@no_children $($tag)+); };
+    (@check_void xhtml ($($tag:tt)+), ($($children:tt)*)) => { $crate::append_html!(@no_children $($tag)+); };
+    (@check_void $type:ident ($($tag:tt)+), ($($children:tt)*)) => {};
+    (@no_children $($tag:tt)+) => {
+        const _: &str = $crate::__void_tag!({
+            compile_error!(concat!("void element `", $crate::append_html!(@stringify_compressed $($tag)+), "` can't have children"))
+        }, $($tag)+);
+    };
+
+    //////// DYNAMIC TAGS
+    (@dialect html) => { $crate::dialect::Html };
+    (@dialect xml) => { $crate::dialect::Xml };
+    (@dialect xhtml) => { $crate::dialect::Xhtml };
+    (@dialect $d:ident) => { $d };
+    (@dynamic_attrs $tmpl:ident, $type:ident,) => {};
+    (@dynamic_attrs $tmpl:ident, $type:ident, $($attrs:tt)+) => {
+        $crate::append_html!(@append_attrs $tmpl, $type, (), $($attrs)+);
+    };
+    // An empty block is the same as no block (so void elements are closed correctly).
+    (@dynamic_tag $tmpl:ident, $type:ident, $name:expr, ($($attrs:tt)*), {}) => {
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ($($attrs)*))
+    };
+    (@dynamic_tag $tmpl:ident, $type:ident, $name:expr, ($($attrs:tt)*), {$($children:tt)+}) => {{
+        let __name = $name;
+        let __tag = $crate::DynamicTag::<$crate::append_html!(@dialect $type)>::new($tmpl, &__name);
+        __tag.open($tmpl);
+        $crate::append_html!(@dynamic_attrs $tmpl, $type, $($attrs)*);
+        let __escaping = __tag.open_end($tmpl);
+        { $crate::append_html!($tmpl, $type, (), $($children)+); }
+        __tag.close($tmpl, __escaping);
+    }};
+    (@dynamic_tag $tmpl:ident, $type:ident, $name:expr, ($($attrs:tt)*)) => {{
+        let __name = $name;
+        let __tag = $crate::DynamicTag::<$crate::append_html!(@dialect $type)>::new($tmpl, &__name);
+        __tag.open($tmpl);
+        $crate::append_html!(@dynamic_attrs $tmpl, $type, $($attrs)*);
+        __tag.close_empty($tmpl);
+    }};
     (@cont $tmpl:ident, $type:ident, ($s:stmt), $($next:tt)*) => {
         $s;
         $crate::append_html!($tmpl, $type, (), $($next)*);
     };
     (@write_const $tmpl:ident, $type:ident,) => {};
-    (@write_const $tmpl:ident, $type:ident, $($p:expr),+) => {
+    (@write_const $tmpl:ident, html, $($p:expr),+) => {
         $tmpl.write_raw(concat!($($p),*));
     };
+    (@write_const $tmpl:ident, xml, $($p:expr),+) => {
+        $tmpl.write_raw(concat!($($p),*));
+    };
+    (@write_const $tmpl:ident, xhtml, $($p:expr),+) => {
+        $tmpl.write_raw(concat!($($p),*));
+    };
+    // The close tags of custom dialects aren't constant.
+    (@write_const $tmpl:ident, $d:ident, $($p:expr),+) => {
+        $($tmpl.write_raw($p);)+
+    };
     (@expr_and_block $tmpl:ident, $type:ident, $goto:ident, ($($prefix:tt)*), {$($inner:tt)*} $($next:tt)*) => {
         $crate::append_html!(@$goto $tmpl, $type, ($($prefix)* {$crate::append_html!($tmpl, $type, (), $($inner)*);}), $($next)*);
     };
@@ -161,21 +341,73 @@ macro_rules! append_html {
         $crate::append_html!(@append_attrs $tmpl, $type, (), $($rest)+);
     };
     (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $($($attr:ident)-+):+ = $value:expr) => {
-        $tmpl.write_raw(concat!($($p,)* " ", $crate::append_html!(@stringify_compressed $($($attr)-+):+), "=\""));
-        $crate::RenderOnce::render_once($value, $tmpl);
+        $crate::__strict_html!($type attribute $($($attr)-+):+);
+        $crate::append_html!(@write_const $tmpl, $type, $($p,)* " ", $crate::append_html!(@stringify_compressed $($($attr)-+):+), "=\"");
+        $crate::append_html!(@attr_value $tmpl, $type, ($($($attr)-+):+), $value);
         $tmpl.write_raw("\"");
     };
-    (@append_attrs $tmpl:ident, html, ($($p:expr),*), $($($attr:ident)-+):+) => {
-        $tmpl.write_raw(concat!($($p,)* " ", $crate::append_html!(@stringify_compressed $($($attr)-+):+)));
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), ..$attrs:expr, $($rest:tt)+) => {
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), ..$attrs);
+        $crate::append_html!(@append_attrs $tmpl, $type, (), $($rest)+);
     };
-    (@append_attrs $tmpl:ident, xml, ($($p:expr),*), $($($attr:ident)-+):+) => {{
-        $tmpl.write_raw(concat!($($p,)* " ",
-            $crate::append_html!(@stringify_compressed $($($attr)-+):+),
-            "=\"",
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), ..$attrs:expr) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::Attrs::spread::<$crate::append_html!(@dialect $type), _>($attrs, $tmpl);
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt ?= $value:expr, $($rest:tt)+) => {
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), $attr ?= $value);
+        $crate::append_html!(@append_attrs $tmpl, $type, (), $($rest)+);
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt ?= $value:expr) => {
+        match $crate::BoolOption::bool_option($value) {
+            (_, None) => {
+                $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+            },
+            (true, Some(_)) => { $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), $attr); }
+            (false, Some(v)) => { $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), $attr = v); }
+        };
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt = $value:expr, $($rest:tt)+) => {
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), $attr = $value);
+        $crate::append_html!(@append_attrs $tmpl, $type, (), $($rest)+);
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt, $($rest:tt)+) => {
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p),*), $attr);
+        $crate::append_html!(@append_attrs $tmpl, $type, (), $($rest)+);
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt = $value:expr) => {
+        $crate::append_html!(@check_name is_valid_attribute_name $attr);
+        $crate::append_html!(@write_const $tmpl, $type, $($p,)* " ", $attr, "=\"");
+        $crate::append_html!(@attr_value $tmpl, $type, ($attr), $value);
+        $tmpl.write_raw("\"");
+    };
+    // The dialect picks the escaping and whether the value is a URL (case insensitively, like
+    // `Attrs`).
+    (@attr_value $tmpl:ident, $type:ident, ($($($attr:ident)-+):+), $value:expr) => {
+        $crate::dialect::render_attribute_value::<$crate::append_html!(@dialect $type), _>(
+            $tmpl, $crate::append_html!(@stringify_compressed $($($attr)-+):+), $value)
+    };
+    (@attr_value $tmpl:ident, $type:ident, ($attr:tt), $value:expr) => {
+        $crate::dialect::render_attribute_value::<$crate::append_html!(@dialect $type), _>($tmpl, $attr, $value)
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $($($attr:ident)-+):+) => {
+        $crate::__strict_html!($type attribute $($($attr)-+):+);
+        $crate::append_html!(@write_const $tmpl, $type, $($p,)* " ",
             $crate::append_html!(@stringify_compressed $($($attr)-+):+),
-            "\""
-        ));
-    }};
+            $crate::append_html!(@boolean_value $type, $crate::append_html!(@stringify_compressed $($($attr)-+):+))
+        );
+    };
+    (@append_attrs $tmpl:ident, $type:ident, ($($p:expr),*), $attr:tt) => {
+        $crate::append_html!(@check_name is_valid_attribute_name $attr);
+        $crate::append_html!(@write_const $tmpl, $type, $($p,)* " ", $attr, $crate::append_html!(@boolean_value $type, $attr));
+    };
+    // XML (and XHTML) attributes always have values.
+    (@boolean_value html, $name:expr) => { "" };
+    (@boolean_value xml, $name:expr) => { concat!("=\"", $name, "\"") };
+    (@boolean_value xhtml, $name:expr) => { concat!("=\"", $name, "\"") };
+    (@boolean_value $d:ident, $name:expr) => {
+        if <$d as $crate::Dialect>::BOOLEAN_ATTRIBUTE_VALUES { concat!("=\"", $name, "\"") } else { "" }
+    };
     //////// IF CHAINS
     //// Begin
     (@parse_if $tmpl:ident, $type:ident, ($($prefix:tt)*), if let $v:pat = $e:tt $($next:tt)+) => {
@@ -197,82 +429,248 @@ macro_rules! append_html {
     (@parse_if_block $tmpl:ident, $type:ident, ($($prefix:tt)*), $($next:tt)*) => {
         $crate::append_html!(@cont $tmpl, $type, ($($prefix)*), $($next)*);
     };
+    //////// MATCH
+    (@parse_match $tmpl:ident, $type:ident, ($($e:tt)*), {$($arms:tt)*} $($next:tt)*) => {
+        $crate::append_html!(@parse_match_arms $tmpl, $type, ($($e)*), (), $($arms)*);
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    (@parse_match $tmpl:ident, $type:ident, ($($e:tt)*), $first:tt $($next:tt)*) => {
+        $crate::append_html!(@parse_match $tmpl, $type, ($($e)* $first), $($next)*);
+    };
+    // Done
+    (@parse_match_arms $tmpl:ident, $type:ident, ($($e:tt)*), ($($arms:tt)*),) => {
+        match $($e)* { $($arms)* }
+    };
+    // Arm
+    (@parse_match_arms $tmpl:ident, $type:ident, ($($e:tt)*), ($($arms:tt)*), $($pat:pat)|+ $(if $guard:expr)? => {$($inner:tt)*}, $($next:tt)*) => {
+        $crate::append_html!(@parse_match_arms $tmpl, $type, ($($e)*), ($($arms)*), $($pat)|+ $(if $guard)? => {$($inner)*} $($next)*);
+    };
+    (@parse_match_arms $tmpl:ident, $type:ident, ($($e:tt)*), ($($arms:tt)*), $($pat:pat)|+ $(if $guard:expr)? => {$($inner:tt)*} $($next:tt)*) => {
+        $crate::append_html!(@parse_match_arms $tmpl, $type, ($($e)*), ($($arms)* $($pat)|+ $(if $guard)? => {$crate::append_html!($tmpl, $type, (), $($inner)*);}), $($next)*);
+    };
+    //////// FOR LOOPS
+    // With else (rendered when the loop body never runs).
+    (@parse_for $tmpl:ident, $type:ident, ($v:pat), ($($e:tt)*), {$($inner:tt)*} else {$($otherwise:tt)*} $($next:tt)*) => {
+        {
+            let mut __empty = true;
+            for $v in $($e)* {
+                $crate::append_html!(@abort_check $tmpl);
+                __empty = false;
+                $crate::append_html!($tmpl, $type, (), $($inner)*);
+            }
+            if __empty {
+                $crate::append_html!($tmpl, $type, (), $($otherwise)*);
+            }
+        }
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    // No else.
+    (@parse_for $tmpl:ident, $type:ident, ($v:pat), ($($e:tt)*), {$($inner:tt)*} $($next:tt)*) => {
+        for $v in $($e)* {
+            $crate::append_html!(@abort_check $tmpl);
+            $crate::append_html!($tmpl, $type, (), $($inner)*);
+        }
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    (@parse_for $tmpl:ident, $type:ident, ($v:pat), ($($e:tt)*), $first:tt $($next:tt)*) => {
+        $crate::append_html!(@parse_for $tmpl, $type, ($v), ($($e)* $first), $($next)*);
+    };
+    //////// WHILE LOOPS
+    (@parse_while $tmpl:ident, $type:ident, ($($head:tt)*), {$($inner:tt)*} $($next:tt)*) => {
+        $($head)* {
+            $crate::append_html!(@abort_check $tmpl);
+            $crate::append_html!($tmpl, $type, (), $($inner)*);
+        }
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    (@parse_while $tmpl:ident, $type:ident, ($($head:tt)*), $first:tt $($next:tt)*) => {
+        $crate::append_html!(@parse_while $tmpl, $type, ($($head)* $first), $($next)*);
+    };
     //// Condition
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ match $($next:tt)+) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@parse_match $tmpl, $type, (), $($next)+);
+    };
     ($tmpl:ident, $type:ident, ($($p:expr),*), @ if $($next:tt)+) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
         $crate::append_html!(@parse_if $tmpl, $type, (), if $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), @ for $v:pat in $e:tt $($next:tt)*) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        $crate::append_html!(@expr_and_block $tmpl, $type, cont, (for $v in $e), $($next)*);
+        $crate::append_html!(@parse_for $tmpl, $type, ($v), ($e), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), @ while let $v:pat = $e:tt $($next:tt)*) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        $crate::append_html!(@expr_and_block $tmpl, $type, cont, (while let $v = $e), $($next)*);
+        $crate::append_html!(@parse_while $tmpl, $type, (while let $v = $e), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), @ while $e:tt $($next:tt)*) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        $crate::append_html!(@expr_and_block $tmpl, $type, cont, (while $e), $($next)*);
+        $crate::append_html!(@parse_while $tmpl, $type, (while $e), $($next)*);
+    };
+    //// Comments
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ comment : $e:expr; $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::Comment($e); $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ comment : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::Comment($e));
+    };
+    //// CDATA sections and processing instructions
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ cdata : $e:expr; $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::CData($e); $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ cdata : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::CData($e));
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ pi($target:expr) : $e:expr; $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::ProcessingInstruction($target, $e); $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ pi($target:expr) : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::ProcessingInstruction($target, $e));
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ pi($target:expr); $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::ProcessingInstruction($target, ""); $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ pi($target:expr)) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), : $crate::ProcessingInstruction($target, ""));
+    };
+    //// Dynamic tags
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)($($attrs:tt)+) : $e:expr; $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), @ tag($name)($($attrs)+) { : $e; } $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)($($attrs:tt)+) : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), @ tag($name)($($attrs)+) { : $e; });
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr) : $e:expr; $($next:tt)*) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), @ tag($name) { : $e; } $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr) : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), @ tag($name) { : $e; });
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)($($attrs:tt)+) { $($children:tt)* } $($next:tt)*) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ($($attrs)+), {$($children)*});
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr) { $($children:tt)* } $($next:tt)*) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, (), {$($children)*});
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)($($attrs:tt)+); $($next:tt)*) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ($($attrs)+));
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)($($attrs:tt)+)) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ($($attrs)+));
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr); $($next:tt)*) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ());
+        $crate::append_html!($tmpl, $type, (), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ tag($name:expr)) => {
+        $crate::append_html!(@write_const $tmpl, $type, $($p),*);
+        $crate::append_html!(@dynamic_tag $tmpl, $type, $name, ());
+    };
+    //// Bindings
+    // Bindings don't write anything so the constant prefix is carried past them.
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ let mut $v:ident : $t:ty = $e:expr; $($next:tt)*) => {
+        let mut $v: $t = $e;
+        $crate::append_html!($tmpl, $type, ($($p),*), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ let $v:ident : $t:ty = $e:expr; $($next:tt)*) => {
+        let $v: $t = $e;
+        $crate::append_html!($tmpl, $type, ($($p),*), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), @ let $v:pat = $e:expr; $($next:tt)*) => {
+        let $v = $e;
+        $crate::append_html!($tmpl, $type, ($($p),*), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), : {$($code:tt)*} $($next:tt)*) => {
         $crate::append_html!(@write_const, $tmpl, $type, $($p),*);
-        $crate::RenderOnce::render_once({$($code)*}, $tmpl);
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            $crate::RenderOnce::render_once({$($code)*}, $tmpl);
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), : $code:expr; $($next:tt)* ) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        $crate::RenderOnce::render_once($code, $tmpl);
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            $crate::RenderOnce::render_once($code, $tmpl);
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), : $code:expr ) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        $crate::RenderOnce::render_once($code, $tmpl);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            $crate::RenderOnce::render_once($code, $tmpl);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), |$var:ident| {$($code:tt)*} $($next:tt)*) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        {
-            let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-            $crate::append_html!(@block_identity {$($code)*})
-        }
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            {
+                let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+                $crate::append_html!(@block_identity {$($code)*})
+            }
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), |mut $var:ident| {$($code:tt)*} $($next:tt)*) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        {
-            let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-            $crate::append_html!(@block_identity {$($code)*})
-        }
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            {
+                let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+                $crate::append_html!(@block_identity {$($code)*})
+            }
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), |$var:ident| $code:stmt; $($next:tt)* ) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        {
-            let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-            $code;
-        }
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            {
+                let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+                $code;
+            }
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), |mut $var:ident| $code:stmt; $($next:tt)* ) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        {
-            let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-            $code;
-        }
-        $crate::append_html!($tmpl, $type, (), $($next)*);
+        $crate::append_html!(@unless_aborted $tmpl, {
+            {
+                let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+                $code;
+            }
+            $crate::append_html!($tmpl, $type, (), $($next)*);
+        });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), |$var:ident| $code:stmt ) => {{
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-        $code;
+        $crate::append_html!(@unless_aborted $tmpl, {
+            let $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+            $code;
+        });
     }};
     ($tmpl:ident, $type:ident, ($($p:expr),*), |mut $var:ident| $code:stmt ) => {{
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
-        let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
-        $code;
+        $crate::append_html!(@unless_aborted $tmpl, {
+            let mut $var: &mut $crate::TemplateBuffer = &mut *$tmpl;
+            $code;
+        });
     }};
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+($($attrs:tt)+) { $($children:tt)* } $($next:tt)* ) => {
+        $crate::append_html!(@check_void $type ($($tag)-+), ($($children)*));
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.open_element($crate::append_html!(@stringify_compressed $($tag)-+));
         $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+)), $($attrs)+);
-        $crate::append_html!($tmpl, $type, (">"), $($children)*);
+        let __escaping = $crate::append_html!(@escaping $type $($tag)-+).map(|e| $tmpl.set_escaping(e));
+        { $crate::append_html!($tmpl, $type, (">"), $($children)*); }
+        if let Some(e) = __escaping { $tmpl.set_escaping(e); }
+        $tmpl.close_element($crate::append_html!(@stringify_compressed $($tag)-+));
         $crate::append_html!($tmpl, $type, ("</", $crate::append_html!(@stringify_compressed $($tag)-+), ">"), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+($($attr:tt)+) : $e:expr; $($next:tt)* ) => {
@@ -285,15 +683,27 @@ macro_rules! append_html {
         $crate::append_html!($tmpl, $type, ($($p),*), $($tag)-+($($attr)+) { : {$($code)*} } $($next)* );
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+($($attrs:tt)+); $($next:tt)*) => {
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.open_element($crate::append_html!(@stringify_compressed $($tag)-+));
         $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+)), $($attrs)+);
+        $tmpl.close_empty_element($crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+));
         $crate::append_html!($tmpl, $type, ($crate::append_html!(@close_tag $type $($tag)-+)), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+($($attrs:tt)+)) => {
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.open_element($crate::append_html!(@stringify_compressed $($tag)-+));
         $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+)), $($attrs)+);
+        $tmpl.close_empty_element($crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+));
         $tmpl.write_raw($crate::append_html!(@close_tag $type $($tag)-+));
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+ { $($children:tt)* } $($next:tt)* ) => {
-        $crate::append_html!($tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+), ">"), $($children)*);
+        $crate::append_html!(@check_void $type ($($tag)-+), ($($children)*));
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.open_element($crate::append_html!(@stringify_compressed $($tag)-+));
+        let __escaping = $crate::append_html!(@escaping $type $($tag)-+).map(|e| $tmpl.set_escaping(e));
+        { $crate::append_html!($tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+), ">"), $($children)*); }
+        if let Some(e) = __escaping { $tmpl.set_escaping(e); }
+        $tmpl.close_element($crate::append_html!(@stringify_compressed $($tag)-+));
         $crate::append_html!($tmpl, $type, ("</", $crate::append_html!(@stringify_compressed $($tag)-+), ">"), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+ : $e:expr; $($next:tt)* ) => {
@@ -303,14 +713,82 @@ macro_rules! append_html {
         $crate::append_html!($tmpl, $type, ($($p),*), $($tag)-+ { : {$($code)*} } $($next)* );
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+; $($next:tt)*) => {
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.empty_element($crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+));
         $crate::append_html!($tmpl, $type, ($($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+)), $($next)*);
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+ : $e:expr) => {
         $crate::append_html!($tmpl, $type, ($($p),*), $($tag)-+ { : $e; });
     };
     ($tmpl:ident, $type:ident, ($($p:expr),*), $($tag:ident)-+) => {
+        $crate::__strict_html!($type element $($tag)-+);
+        $tmpl.empty_element($crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+));
         $crate::append_html!(@write_const $tmpl, $type, $($p,)* "<", $crate::append_html!(@stringify_compressed $($tag)-+), $crate::append_html!(@close_tag $type $($tag)-+));
     };
+    //// String literal tag names
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attrs:tt)+) { $($children:tt)* } $($next:tt)* ) => {
+        $crate::append_html!(@check_void $type ($tag), ($($children)*));
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.open_element($tag);
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $tag), $($attrs)+);
+        let __escaping = $crate::append_html!(@escaping $type $tag).map(|e| $tmpl.set_escaping(e));
+        { $crate::append_html!($tmpl, $type, (">"), $($children)*); }
+        if let Some(e) = __escaping { $tmpl.set_escaping(e); }
+        $tmpl.close_element($tag);
+        $crate::append_html!($tmpl, $type, ("</", $tag, ">"), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attr:tt)+) : $e:expr; $($next:tt)* ) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag($($attr)+) { : $e; } $($next)* );
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attr:tt)+) : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag($($attr)+) { : $e });
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attr:tt)+) : {$($code:tt)*} $($next:tt)* ) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag($($attr)+) { : {$($code)*} } $($next)* );
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attrs:tt)+); $($next:tt)*) => {
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.open_element($tag);
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $tag), $($attrs)+);
+        $tmpl.close_empty_element($tag, $crate::append_html!(@close_tag $type $tag));
+        $crate::append_html!($tmpl, $type, ($crate::append_html!(@close_tag $type $tag)), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt($($attrs:tt)+)) => {
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.open_element($tag);
+        $crate::append_html!(@append_attrs $tmpl, $type, ($($p,)* "<", $tag), $($attrs)+);
+        $tmpl.close_empty_element($tag, $crate::append_html!(@close_tag $type $tag));
+        $tmpl.write_raw($crate::append_html!(@close_tag $type $tag));
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt { $($children:tt)* } $($next:tt)* ) => {
+        $crate::append_html!(@check_void $type ($tag), ($($children)*));
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.open_element($tag);
+        let __escaping = $crate::append_html!(@escaping $type $tag).map(|e| $tmpl.set_escaping(e));
+        { $crate::append_html!($tmpl, $type, ($($p,)* "<", $tag, ">"), $($children)*); }
+        if let Some(e) = __escaping { $tmpl.set_escaping(e); }
+        $tmpl.close_element($tag);
+        $crate::append_html!($tmpl, $type, ("</", $tag, ">"), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt : $e:expr; $($next:tt)* ) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag { : $e; } $($next)* );
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt : {$($code:tt)*} $($next:tt)* ) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag { : {$($code)*} } $($next)* );
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt; $($next:tt)*) => {
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.empty_element($tag, $crate::append_html!(@close_tag $type $tag));
+        $crate::append_html!($tmpl, $type, ($($p,)* "<", $tag, $crate::append_html!(@close_tag $type $tag)), $($next)*);
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt : $e:expr) => {
+        $crate::append_html!($tmpl, $type, ($($p),*), $tag { : $e; });
+    };
+    ($tmpl:ident, $type:ident, ($($p:expr),*), $tag:tt) => {
+        $crate::append_html!(@check_name is_valid_tag_name $tag);
+        $tmpl.empty_element($tag, $crate::append_html!(@close_tag $type $tag));
+        $crate::append_html!(@write_const $tmpl, $type, $($p,)* "<", $tag, $crate::append_html!(@close_tag $type $tag));
+    };
     ($tmpl:ident, $type:ident, ($($p:expr),*),) => {
         $crate::append_html!(@write_const $tmpl, $type, $($p),*);
     };
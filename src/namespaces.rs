@@ -0,0 +1,124 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::parse::{Handler, Tokenizer};
+
+/// The namespace the `xml` prefix is permanently bound to.
+const XML_NAMESPACE: &[u8] = b"http://www.w3.org/XML/1998/namespace";
+
+/// Checks that every namespace prefix used in the markup written through it has been declared
+/// (with an `xmlns:prefix` attribute on the element or one of its ancestors).
+pub(crate) struct NamespaceChecker {
+    tokenizer: Tokenizer,
+    scopes: Scopes,
+}
+
+/// The prefixes declared by the open elements.
+struct Scopes {
+    declared: Vec<Vec<u8>>,
+    /// The number of prefixes declared by each open element.
+    counts: Vec<usize>,
+}
+
+fn prefix(name: &[u8]) -> Option<&[u8]> {
+    name.iter().position(|&b| b == b':').map(|i| &name[..i])
+}
+
+impl NamespaceChecker {
+    pub(crate) fn new() -> Self {
+        NamespaceChecker {
+            tokenizer: Tokenizer::new(),
+            scopes: Scopes {
+                declared: Vec::new(),
+                counts: Vec::new(),
+            },
+        }
+    }
+
+    /// Parse the next chunk of markup, returning an error message for the first problem found.
+    pub(crate) fn write(&mut self, text: &str) -> Option<String> {
+        self.tokenizer.write(text, &mut self.scopes)
+    }
+}
+
+impl Scopes {
+    /// Returns true if `prefix` is declared in the current scope.
+    fn is_declared(&self, prefix: &[u8]) -> bool {
+        prefix == b"xml" || self.declared.iter().any(|p| &p[..] == prefix)
+    }
+
+    fn check_name(&self, name: &[u8], kind: &str) -> Option<String> {
+        match prefix(name) {
+            Some(p) if p == b"xmlns" || !self.is_declared(p) => Some(format!(
+                "undeclared namespace prefix `{}` on {} `{}`",
+                String::from_utf8_lossy(p),
+                kind,
+                String::from_utf8_lossy(name)
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Handler for Scopes {
+    fn start_tag(
+        &mut self,
+        element: &[u8],
+        attrs: &[(Vec<u8>, Vec<u8>)],
+        self_closing: bool,
+    ) -> Option<String> {
+        let mut error = None;
+        let mut count = 0;
+        for (name, value) in attrs {
+            if !name.starts_with(b"xmlns:") {
+                continue;
+            }
+            let declared = &name[6..];
+            if value.is_empty() {
+                error = error.or_else(|| {
+                    Some(format!(
+                        "namespace prefix `{}` is declared with an empty URI",
+                        String::from_utf8_lossy(declared)
+                    ))
+                });
+            } else if declared == b"xmlns" || (declared == b"xml") != (value[..] == *XML_NAMESPACE)
+            {
+                error = error.or_else(|| {
+                    Some(format!(
+                        "namespace prefix `{}` can't be bound to `{}`",
+                        String::from_utf8_lossy(declared),
+                        String::from_utf8_lossy(value)
+                    ))
+                });
+            }
+            self.declared.push(declared.to_vec());
+            count += 1;
+        }
+        if error.is_none() {
+            error = self.check_name(element, "element");
+        }
+        if error.is_none() {
+            error = attrs
+                .iter()
+                .filter(|(name, _)| &name[..] != b"xmlns" && !name.starts_with(b"xmlns:"))
+                .filter_map(|(name, _)| self.check_name(name, "attribute"))
+                .next();
+        }
+        if self_closing {
+            let len = self.declared.len();
+            self.declared.truncate(len - count);
+        } else {
+            self.counts.push(count);
+        }
+        error
+    }
+
+    fn end_tag(&mut self, _name: &[u8]) -> Option<String> {
+        if let Some(n) = self.counts.pop() {
+            let len = self.declared.len();
+            self.declared.truncate(len - n);
+        }
+        None
+    }
+}
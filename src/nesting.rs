@@ -0,0 +1,222 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Flow content that isn't phrasing content (and therefore can't appear in a paragraph, a heading,
+/// a `span`, etc.).
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "li",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Elements that may only contain phrasing content.
+const PHRASING_ELEMENTS: &[&str] = &[
+    "abbr", "b", "bdi", "bdo", "button", "cite", "code", "data", "dfn", "em", "h1", "h2", "h3",
+    "h4", "h5", "h6", "i", "kbd", "label", "legend", "mark", "output", "p", "pre", "q", "s",
+    "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var",
+];
+
+/// Interactive content (which can't appear inside `a` or `button` elements).
+const INTERACTIVE_ELEMENTS: &[&str] = &[
+    "a", "button", "details", "embed", "iframe", "input", "label", "select", "textarea",
+];
+
+/// Elements that may only appear as children of specific elements.
+const PARENTS: &[(&str, &[&str])] = &[
+    ("caption", &["table"]),
+    ("colgroup", &["table"]),
+    ("dd", &["dl", "div"]),
+    ("dt", &["dl", "div"]),
+    ("li", &["ul", "ol", "menu"]),
+    ("optgroup", &["select"]),
+    ("option", &["select", "datalist", "optgroup"]),
+    ("summary", &["details"]),
+    ("tbody", &["table"]),
+    ("td", &["tr"]),
+    ("tfoot", &["table"]),
+    ("th", &["tr"]),
+    ("thead", &["table"]),
+    ("tr", &["table", "thead", "tbody", "tfoot"]),
+];
+
+fn contains(list: &[&str], name: &str) -> bool {
+    list.contains(&name)
+}
+
+/// Checks that the elements opened by templates follow (a subset of) the HTML5 content models.
+///
+/// This is driven by the element events `TemplateBuffer` receives from the template macros and
+/// `DynamicTag` (raw output isn't checked).
+#[derive(Clone)]
+pub(crate) struct NestingChecker {
+    /// The open elements (lowercase).
+    elements: Vec<String>,
+    /// The `a` or `button` element containing the open `input` element. It's only an error if the
+    /// input isn't hidden and we don't know that until we've seen its attributes.
+    input_in: Option<String>,
+    /// The value of the open `input` element's `type` attribute.
+    input_type: String,
+    /// The `type` attribute of the open `input` element is being written.
+    capturing: bool,
+}
+
+impl NestingChecker {
+    pub(crate) fn new() -> Self {
+        NestingChecker {
+            elements: Vec::new(),
+            input_in: None,
+            input_type: String::new(),
+            capturing: false,
+        }
+    }
+
+    /// An element has been opened. Returns an error message if it can't appear here.
+    pub(crate) fn open(&mut self, name: &str) -> Option<String> {
+        let name = name.to_ascii_lowercase();
+        let error = self.check(&name);
+        self.elements.push(name);
+        error
+    }
+
+    /// An element without attributes or children has been written.
+    pub(crate) fn empty(&mut self, name: &str) -> Option<String> {
+        let name = name.to_ascii_lowercase();
+        self.check(&name).or_else(|| self.input_error())
+    }
+
+    /// The innermost open element has been closed.
+    pub(crate) fn close(&mut self) -> Option<String> {
+        if self.elements.pop().as_deref() != Some("input") {
+            return None;
+        }
+        if self.input_type.eq_ignore_ascii_case("hidden") {
+            self.input_in = None;
+        }
+        self.input_error()
+    }
+
+    /// The value of the attribute `name` is about to be written.
+    pub(crate) fn start_attribute_value(&mut self, name: &str) {
+        self.capturing = self.input_in.is_some()
+            && self.elements.last().map(|e| &e[..]) == Some("input")
+            && name.eq_ignore_ascii_case("type");
+        if self.capturing {
+            self.input_type.clear();
+        }
+    }
+
+    pub(crate) fn end_attribute_value(&mut self) {
+        self.capturing = false;
+    }
+
+    /// Returns true if the output should be passed to `capture`.
+    #[inline]
+    pub(crate) fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    pub(crate) fn capture(&mut self, text: &str) {
+        self.input_type.push_str(text);
+    }
+
+    fn input_error(&mut self) -> Option<String> {
+        self.input_in.take().map(|parent| {
+            format!(
+                "interactive element `<input>` can't appear inside `<{}>`",
+                parent
+            )
+        })
+    }
+
+    /// Returns the innermost open element in `list`, stopping at `template` elements (the
+    /// contents of which are a separate document fragment).
+    fn ancestor(&self, list: &[&str]) -> Option<&str> {
+        self.elements
+            .iter()
+            .rev()
+            .take_while(|e| *e != "template")
+            .find(|e| contains(list, e))
+            .map(|e| &e[..])
+    }
+
+    fn check(&mut self, name: &str) -> Option<String> {
+        if contains(BLOCK_ELEMENTS, name) {
+            if let Some(parent) = self.ancestor(PHRASING_ELEMENTS) {
+                return Some(format!(
+                    "`<{}>` can't appear inside `<{}>` (which may only contain phrasing content)",
+                    name, parent
+                ));
+            }
+        }
+        if contains(INTERACTIVE_ELEMENTS, name) {
+            if let Some(parent) = self.ancestor(&["a", "button"]) {
+                // Hidden inputs are fine (checked once we've seen the attributes).
+                if name == "input" {
+                    self.input_in = Some(String::from(parent));
+                    self.input_type.clear();
+                } else {
+                    return Some(format!(
+                        "interactive element `<{}>` can't appear inside `<{}>`",
+                        name, parent
+                    ));
+                }
+            }
+        }
+        if name == "form" && self.ancestor(&["form"]).is_some() {
+            return Some(String::from(
+                "`<form>` can't appear inside another `<form>`",
+            ));
+        }
+        // Only check parents when we know them (the template may be a fragment).
+        if let (Some(&(_, parents)), Some(parent)) = (
+            PARENTS.iter().find(|(e, _)| *e == name),
+            self.elements.last(),
+        ) {
+            if !contains(parents, parent) && parent != "template" {
+                let names: Vec<String> = parents.iter().map(|p| format!("`<{}>`", p)).collect();
+                let expected = match names.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{} or {}", rest.join(", "), last)
+                    }
+                    _ => names.concat(),
+                };
+                return Some(format!(
+                    "`<{}>` must be a child of {}, not `<{}>`",
+                    name, expected, parent
+                ));
+            }
+        }
+        None
+    }
+}
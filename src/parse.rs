@@ -0,0 +1,264 @@
+//! A minimal streaming tokenizer for checking the markup written by templates.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Receives the tags found by a `Tokenizer`.
+pub(crate) trait Handler {
+    /// Called at the end of each start tag. Returns an error message if the tag is invalid.
+    fn start_tag(
+        &mut self,
+        name: &[u8],
+        attrs: &[(Vec<u8>, Vec<u8>)],
+        self_closing: bool,
+    ) -> Option<String>;
+
+    /// Called at the end of each end tag. Returns an error message if the tag is invalid.
+    fn end_tag(&mut self, name: &[u8]) -> Option<String>;
+}
+
+enum State {
+    Text,
+    /// Just saw a `<`.
+    Lt,
+    /// Reading the name of a start tag.
+    StartName,
+    /// Inside a start tag, between attributes.
+    InTag,
+    /// Just saw a `/` inside a start tag.
+    SelfClose,
+    /// Reading the name of an attribute.
+    AttrName,
+    /// After an attribute name, looking for an `=`.
+    AfterAttrName,
+    /// After an `=`, looking for the value.
+    BeforeValue,
+    /// Inside an attribute value (quoted with the given byte, or unquoted if 0).
+    Value(u8),
+    /// Reading the name of an end tag.
+    EndName,
+    /// Inside an end tag, after the name.
+    EndTag,
+    /// Just saw `<!`.
+    Bang,
+    /// Just saw `<!-`.
+    CommentStart,
+    /// Reading `<![CDATA[` (records how much of it has been seen).
+    CDataStart(usize),
+    /// Inside a comment, counting trailing dashes.
+    Comment(usize),
+    /// Inside a CDATA section, counting trailing brackets.
+    CData(usize),
+    /// Inside a processing instruction. Records whether the last byte was a `?`.
+    Pi(bool),
+    /// Inside some other declaration (e.g., a `DOCTYPE`).
+    Decl,
+}
+
+/// Splits markup into start and end tags, skipping text, comments, CDATA sections, processing
+/// instructions, and other declarations.
+pub(crate) struct Tokenizer {
+    state: State,
+    /// The name of the current attribute.
+    name: Vec<u8>,
+    /// The current attribute value.
+    value: Vec<u8>,
+    /// The name of the tag currently being parsed.
+    element: Vec<u8>,
+    attrs: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Tokenizer {
+    pub(crate) fn new() -> Self {
+        Tokenizer {
+            state: State::Text,
+            name: Vec::new(),
+            value: Vec::new(),
+            element: Vec::new(),
+            attrs: Vec::new(),
+        }
+    }
+
+    /// Parse the next chunk of markup, returning the first error reported by the handler.
+    pub(crate) fn write<H: Handler>(&mut self, text: &str, handler: &mut H) -> Option<String> {
+        let mut error = None;
+        for &b in text.as_bytes() {
+            let e = self.step(b, handler);
+            if error.is_none() {
+                error = e;
+            }
+        }
+        error
+    }
+
+    fn step<H: Handler>(&mut self, b: u8, handler: &mut H) -> Option<String> {
+        match self.state {
+            State::Text => {
+                if b == b'<' {
+                    self.state = State::Lt;
+                }
+            }
+            State::Lt => {
+                self.state = match b {
+                    b'/' => {
+                        self.element.clear();
+                        State::EndName
+                    }
+                    b'!' => State::Bang,
+                    b'?' => State::Pi(false),
+                    b'<' => State::Lt,
+                    _ if b.is_ascii_whitespace() || b == b'>' => State::Text,
+                    _ => {
+                        self.element.clear();
+                        self.element.push(b);
+                        self.attrs.clear();
+                        State::StartName
+                    }
+                }
+            }
+            State::StartName => match b {
+                b'>' => return self.end_start_tag(false, handler),
+                b'/' => self.state = State::SelfClose,
+                _ if b.is_ascii_whitespace() => self.state = State::InTag,
+                _ => self.element.push(b),
+            },
+            State::InTag => return self.in_tag(b, handler),
+            State::SelfClose => {
+                if b == b'>' {
+                    return self.end_start_tag(true, handler);
+                }
+                return self.in_tag(b, handler);
+            }
+            State::AttrName => match b {
+                b'=' => self.state = State::BeforeValue,
+                b'>' | b'/' => {
+                    self.push_attr();
+                    return self.in_tag(b, handler);
+                }
+                _ if b.is_ascii_whitespace() => self.state = State::AfterAttrName,
+                _ => self.name.push(b),
+            },
+            State::AfterAttrName => match b {
+                b'=' => self.state = State::BeforeValue,
+                _ if b.is_ascii_whitespace() => (),
+                _ => {
+                    self.push_attr();
+                    return self.in_tag(b, handler);
+                }
+            },
+            State::BeforeValue => match b {
+                b'"' | b'\'' => self.state = State::Value(b),
+                _ if b.is_ascii_whitespace() => (),
+                _ => {
+                    self.value.push(b);
+                    self.state = State::Value(0);
+                }
+            },
+            State::Value(0) => {
+                if b == b'>' || b.is_ascii_whitespace() {
+                    self.push_attr();
+                    return self.in_tag(b, handler);
+                }
+                self.value.push(b);
+            }
+            State::Value(quote) => {
+                if b == quote {
+                    self.push_attr();
+                    self.state = State::InTag;
+                } else {
+                    self.value.push(b);
+                }
+            }
+            State::EndName => match b {
+                b'>' => {
+                    self.state = State::Text;
+                    return handler.end_tag(&self.element);
+                }
+                _ if b.is_ascii_whitespace() => self.state = State::EndTag,
+                _ => self.element.push(b),
+            },
+            State::EndTag => {
+                if b == b'>' {
+                    self.state = State::Text;
+                    return handler.end_tag(&self.element);
+                }
+            }
+            State::Bang => {
+                self.state = match b {
+                    b'-' => State::CommentStart,
+                    b'[' => State::CDataStart(1),
+                    b'>' => State::Text,
+                    _ => State::Decl,
+                }
+            }
+            State::CommentStart => {
+                self.state = if b == b'-' {
+                    State::Comment(0)
+                } else {
+                    State::Decl
+                }
+            }
+            State::CDataStart(seen) => {
+                const CDATA: &[u8] = b"[CDATA[";
+                self.state = if CDATA[seen] != b {
+                    State::Decl
+                } else if seen + 1 == CDATA.len() {
+                    State::CData(0)
+                } else {
+                    State::CDataStart(seen + 1)
+                }
+            }
+            State::Comment(dashes) => {
+                self.state = match b {
+                    b'>' if dashes >= 2 => State::Text,
+                    b'-' => State::Comment(dashes + 1),
+                    _ => State::Comment(0),
+                }
+            }
+            State::CData(brackets) => {
+                self.state = match b {
+                    b'>' if brackets >= 2 => State::Text,
+                    b']' => State::CData(brackets + 1),
+                    _ => State::CData(0),
+                }
+            }
+            State::Pi(question) => {
+                self.state = match b {
+                    b'>' if question => State::Text,
+                    _ => State::Pi(b == b'?'),
+                }
+            }
+            State::Decl => {
+                if b == b'>' {
+                    self.state = State::Text;
+                }
+            }
+        }
+        None
+    }
+
+    /// Handle a byte inside a start tag, between attributes.
+    fn in_tag<H: Handler>(&mut self, b: u8, handler: &mut H) -> Option<String> {
+        match b {
+            b'>' => return self.end_start_tag(false, handler),
+            b'/' => self.state = State::SelfClose,
+            _ if b.is_ascii_whitespace() => self.state = State::InTag,
+            _ => {
+                self.name.push(b);
+                self.state = State::AttrName;
+            }
+        }
+        None
+    }
+
+    fn push_attr(&mut self) {
+        let name = core::mem::take(&mut self.name);
+        let value = core::mem::take(&mut self.value);
+        self.attrs.push((name, value));
+    }
+
+    fn end_start_tag<H: Handler>(&mut self, self_closing: bool, handler: &mut H) -> Option<String> {
+        self.state = State::Text;
+        handler.start_tag(&self.element, &self.attrs, self_closing)
+    }
+}
@@ -5,6 +5,7 @@ pub use crate::RenderMut;
 pub use crate::RenderOnce;
 pub use crate::Template;
 pub use crate::TemplateBuffer;
+pub use crate::WithLoop;
 
 #[cfg(feature = "alloc")]
 pub use crate::RenderBox;
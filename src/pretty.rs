@@ -0,0 +1,146 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Elements whose content is whitespace sensitive (and is therefore never reformatted).
+const PREFORMATTED_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Elements laid out inline with the surrounding text (where added whitespace would be visible).
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "audio", "b", "bdi", "bdo", "br", "button", "canvas", "cite", "code", "data",
+    "del", "dfn", "em", "embed", "i", "iframe", "img", "input", "ins", "kbd", "label", "map",
+    "mark", "math", "meter", "object", "output", "picture", "progress", "q", "rp", "rt", "ruby",
+    "s", "samp", "select", "slot", "small", "span", "strong", "sub", "sup", "svg", "time", "u",
+    "var", "video", "wbr",
+];
+
+const INDENT: &str = "  ";
+
+fn is_one_of(name: &str, names: &[&str]) -> bool {
+    names.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Where the elements written so far leave the output (see `PrettyWriter`).
+#[derive(Clone, Default)]
+pub(crate) struct Layout {
+    /// The open elements: whether they contain any block elements and whether their content is
+    /// left as-is (inline and preformatted elements).
+    stack: Vec<(bool, bool)>,
+    /// The number of open elements whose content is left as-is.
+    as_is: usize,
+    /// One entry for the `<` of each tag that has been announced but not written yet: the depth
+    /// to indent it to or `None` to leave it where it is.
+    breaks: VecDeque<Option<usize>>,
+    /// Output has already been written elsewhere (when rendering into a scratch buffer).
+    started: bool,
+}
+
+/// Writer that pretty-prints the markup written through it.
+///
+/// Block elements (anything not in `INLINE_ELEMENTS`), comments and processing
+/// instructions start on a new line, indented by their depth. End tags are placed on their own
+/// line when the element contains block elements. Inline elements and the contents of inline and
+/// whitespace sensitive elements are left as they are so the added whitespace never ends up in
+/// the middle of a line of text.
+///
+/// The output isn't parsed: `TemplateBuffer` announces every element it opens and closes before
+/// writing its tags, and line breaks are inserted in front of the corresponding `<`s. Anything
+/// else (text and raw markup) is written as-is.
+pub(crate) struct PrettyWriter<'a> {
+    pub(crate) out: &'a mut String,
+    pub(crate) layout: Layout,
+}
+
+impl<'a> PrettyWriter<'a> {
+    pub(crate) fn new(out: &'a mut String) -> Self {
+        PrettyWriter {
+            out: out,
+            layout: Layout::default(),
+        }
+    }
+
+    /// Returns a writer that continues this writer's layout in `out`.
+    pub(crate) fn fork<'b>(&self, out: &'b mut String) -> PrettyWriter<'b> {
+        let mut layout = self.layout.clone();
+        layout.started |= !self.out.is_empty();
+        PrettyWriter {
+            out: out,
+            layout: layout,
+        }
+    }
+
+    /// The start tag of an element is about to be written.
+    pub(crate) fn open(&mut self, name: &str) {
+        let inline = is_one_of(name, INLINE_ELEMENTS);
+        if inline {
+            self.layout.breaks.push_back(None);
+        } else {
+            self.block();
+        }
+        let as_is = inline || is_one_of(name, PREFORMATTED_ELEMENTS);
+        if as_is {
+            self.layout.as_is += 1;
+        }
+        self.layout.stack.push((false, as_is));
+    }
+
+    /// The innermost open element is about to be closed, with an end tag if `end_tag` is true
+    /// (and by finishing its start tag otherwise).
+    pub(crate) fn close(&mut self, end_tag: bool) {
+        let layout = &mut self.layout;
+        let (blocks, as_is) = layout.stack.pop().unwrap_or((false, false));
+        if end_tag {
+            let depth = layout.stack.len();
+            let indent = if blocks && layout.as_is == 0 {
+                Some(depth)
+            } else {
+                None
+            };
+            layout.breaks.push_back(indent);
+        }
+        if as_is {
+            layout.as_is -= 1;
+        }
+    }
+
+    /// A comment or processing instruction is about to be written.
+    pub(crate) fn markup(&mut self) {
+        self.block();
+    }
+
+    /// Start a new line for the next tag unless we're inside an element that's left as-is.
+    fn block(&mut self) {
+        let layout = &mut self.layout;
+        if layout.as_is > 0 {
+            layout.breaks.push_back(None);
+            return;
+        }
+        let depth = layout.stack.len();
+        if let Some(parent) = layout.stack.last_mut() {
+            parent.0 = true;
+        }
+        layout.breaks.push_back(Some(depth));
+    }
+
+    pub(crate) fn write(&mut self, text: &str) {
+        let mut rest = text;
+        while !self.layout.breaks.is_empty() {
+            let i = match rest.find('<') {
+                Some(i) => i,
+                None => break,
+            };
+            self.out.push_str(&rest[..i]);
+            if let Some(Some(depth)) = self.layout.breaks.pop_front() {
+                if self.layout.started || !self.out.is_empty() {
+                    self.out.push('\n');
+                    for _ in 0..depth {
+                        self.out.push_str(INDENT);
+                    }
+                }
+            }
+            self.out.push('<');
+            rest = &rest[i + 1..];
+        }
+        self.out.push_str(rest);
+    }
+}
@@ -1,9 +1,22 @@
 use core::fmt;
 
 #[cfg(feature = "alloc")]
-use alloc::{boxed::Box, string::String};
+use alloc::{boxed::Box, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::borrow::Borrow;
+#[cfg(feature = "alloc")]
+use core::iter::FromIterator;
 
+#[cfg(feature = "alloc")]
+use crate::dialect::Dialect;
+#[cfg(feature = "alloc")]
+use crate::error::Error;
+use crate::json::ToJson;
+#[cfg(feature = "alloc")]
+use crate::tag::is_valid_attribute_name;
+use crate::tag::is_valid_tag_name;
 use crate::template::{Template, TemplateBuffer};
+use crate::url::UrlWriter;
 
 /// Something that can be rendered once.
 pub trait RenderOnce {
@@ -32,7 +45,7 @@ pub trait Render: RenderMut {
 
 // RenderOnce is the trait we really care about.
 
-impl<'a, T: ?Sized> RenderOnce for &'a mut T
+impl<T: ?Sized> RenderOnce for &mut T
 where
     T: RenderMut,
 {
@@ -44,7 +57,7 @@ where
     }
 }
 
-impl<'a, T: ?Sized> RenderOnce for &'a T
+impl<T: ?Sized> RenderOnce for &T
 where
     T: Render,
 {
@@ -253,7 +266,9 @@ where
     F: FnOnce(&mut TemplateBuffer<'_>),
 {
     fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
-        (self.renderer)(tmpl)
+        if !tmpl.is_aborted() {
+            (self.renderer)(tmpl)
+        }
     }
 
     fn size_hint(&self) -> usize {
@@ -266,7 +281,9 @@ where
     F: FnMut(&mut TemplateBuffer<'_>),
 {
     fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
-        (self.renderer)(tmpl)
+        if !tmpl.is_aborted() {
+            (self.renderer)(tmpl)
+        }
     }
 }
 
@@ -275,7 +292,9 @@ where
     F: Fn(&mut TemplateBuffer<'_>),
 {
     fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
-        (self.renderer)(tmpl)
+        if !tmpl.is_aborted() {
+            (self.renderer)(tmpl)
+        }
     }
 }
 
@@ -378,7 +397,7 @@ where
     }
 }
 
-impl<'a> RenderOnce for &'a str {
+impl RenderOnce for &str {
     #[inline]
     fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
         tmpl.write_str(self)
@@ -390,14 +409,14 @@ impl<'a> RenderOnce for &'a str {
     }
 }
 
-impl<'a> RenderMut for &'a str {
+impl RenderMut for &str {
     #[inline]
     fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
         tmpl.write_str(self)
     }
 }
 
-impl<'a> Render for &'a str {
+impl Render for &str {
     #[inline]
     fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
         tmpl.write_str(self)
@@ -616,3 +635,814 @@ where
         }
     }
 }
+
+/// Render the inner value as JSON.
+///
+/// The JSON is written through the template's escaping writer so it's escaped appropriately for
+/// where it ends up: left as-is inside `script` elements (e.g., `<script type="application/json">`)
+/// and HTML-escaped in attributes and text. Strings are always written with `<`, `>`, `&`, U+2028
+/// and U+2029 escaped so the JSON can't close the enclosing script element.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{Json, Template};
+/// # fn main() {
+/// let state = vec!["</script>", "ok"];
+/// let page = html! {
+///     script(type="application/json", id="state") : Json(&state);
+/// };
+/// assert_eq!(
+///     page.into_string().unwrap(),
+///     "<script type=\"application/json\" id=\"state\">[\"\\u003c/script\\u003e\",\"ok\"]</script>"
+/// );
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Json<T>(pub T);
+
+impl<T> RenderOnce for Json<T>
+where
+    T: ToJson,
+{
+    #[inline]
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+}
+
+impl<T> RenderMut for Json<T>
+where
+    T: ToJson,
+{
+    #[inline]
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+}
+
+impl<T> Render for Json<T>
+where
+    T: ToJson,
+{
+    #[inline]
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        // Writing to the template never fails; errors are recorded on the buffer.
+        let _ = self.0.write_json(&mut tmpl.as_writer());
+    }
+}
+
+/// Render the inner template as a URL attribute value.
+///
+/// `html!` automatically wraps the values of attributes that contain URLs (`href`, `src`,
+/// `action`, etc.) in `Url`.
+///
+/// Characters that aren't allowed in URLs (whitespace, quotes, non-ascii characters, etc.) are
+/// percent-encoded and the output is HTML escaped. If the URL has a scheme other than `http`,
+/// `https`, `ftp`, `mailto` or `tel` (e.g., `javascript:`), an error is recorded instead.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::Template;
+/// # fn main() {
+/// let ok = html! { a(href="/search?q=hello world&lang=en") };
+/// assert_eq!(
+///     ok.into_string().unwrap(),
+///     "<a href=\"/search?q=hello%20world&amp;lang=en\"></a>"
+/// );
+///
+/// let bad = html! { a(href="javascript:alert(1)") };
+/// assert!(bad.into_string().is_err());
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Url<R>(pub R);
+
+impl<R> RenderOnce for Url<R>
+where
+    R: RenderOnce,
+{
+    #[inline]
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>)
+    where
+        Self: Sized,
+    {
+        if let Err(err) = self.0.write_to_fmt(&mut UrlWriter::new(tmpl)) {
+            tmpl.record_error(err);
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<R> RenderMut for Url<R>
+where
+    R: RenderMut,
+{
+    #[inline]
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>)
+    where
+        Self: Sized,
+    {
+        if let Err(err) = (&mut self.0).write_to_fmt(&mut UrlWriter::new(tmpl)) {
+            tmpl.record_error(err);
+        }
+    }
+}
+
+impl<R> Render for Url<R>
+where
+    R: Render,
+{
+    #[inline]
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>)
+    where
+        Self: Sized,
+    {
+        if let Err(err) = (&self.0).write_to_fmt(&mut UrlWriter::new(tmpl)) {
+            tmpl.record_error(err);
+        }
+    }
+}
+
+/// Comment marker.
+///
+/// Renders the inner text as a comment (`<!-- text -->`). The text isn't escaped but `--` is
+/// broken up (`- -`) so the text can't end the comment early (this also keeps the comment valid
+/// XML).
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{Comment, Template};
+/// # fn main() {
+/// let page = html! { : Comment("a --> b") };
+/// assert_eq!(page.into_string().unwrap(), "<!-- a - -> b -->");
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Comment<S: AsRef<str>>(pub S);
+
+impl<S> RenderOnce for Comment<S>
+where
+    S: AsRef<str>,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len() + 9
+    }
+}
+
+impl<S> RenderMut for Comment<S>
+where
+    S: AsRef<str>,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+}
+
+impl<S> Render for Comment<S>
+where
+    S: AsRef<str>,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let text = self.0.as_ref();
+        tmpl.start_markup();
+        tmpl.write_raw("<!-- ");
+        let bytes = text.as_bytes();
+        let mut start = 0;
+        for i in 1..bytes.len() {
+            if bytes[i] == b'-' && bytes[i - 1] == b'-' {
+                tmpl.write_raw(&text[start..i]);
+                tmpl.write_raw(" ");
+                start = i;
+            }
+        }
+        tmpl.write_raw(&text[start..]);
+        tmpl.write_raw(" -->");
+    }
+}
+
+/// CDATA section marker.
+///
+/// Renders the inner text as a CDATA section (`<![CDATA[text]]>`). The text isn't escaped; any
+/// `]]>` in it is split across two CDATA sections so it can't end the section early.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{CData, Template};
+/// # fn main() {
+/// let doc = xml! { script : CData("if (a]]>b) {}") };
+/// assert_eq!(
+///     doc.into_string().unwrap(),
+///     "<script><![CDATA[if (a]]]]><![CDATA[>b) {}]]></script>"
+/// );
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct CData<S: AsRef<str>>(pub S);
+
+impl<S> RenderOnce for CData<S>
+where
+    S: AsRef<str>,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len() + 12
+    }
+}
+
+impl<S> RenderMut for CData<S>
+where
+    S: AsRef<str>,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+}
+
+impl<S> Render for CData<S>
+where
+    S: AsRef<str>,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let text = self.0.as_ref();
+        tmpl.write_raw("<![CDATA[");
+        let mut start = 0;
+        for (i, _) in text.match_indices("]]>") {
+            // End the section between the `]]` and the `>`.
+            tmpl.write_raw(&text[start..i + 2]);
+            tmpl.write_raw("]]><![CDATA[");
+            start = i + 2;
+        }
+        tmpl.write_raw(&text[start..]);
+        tmpl.write_raw("]]>");
+    }
+}
+
+/// Processing instruction marker.
+///
+/// Renders a processing instruction (`<?target data?>`). Neither the target nor the data are
+/// escaped. An error is recorded instead if the target isn't a valid name (or is the reserved
+/// `xml`) or if the data contains `?>`. Use `helper::declaration::XML` for the XML declaration.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{ProcessingInstruction, Template};
+/// # fn main() {
+/// let doc = xml! {
+///     : ProcessingInstruction("xml-stylesheet", "type=\"text/xsl\" href=\"style.xsl\"");
+///     root;
+/// };
+/// assert_eq!(
+///     doc.into_string().unwrap(),
+///     "<?xml-stylesheet type=\"text/xsl\" href=\"style.xsl\"?><root/>"
+/// );
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct ProcessingInstruction<T: AsRef<str>, S: AsRef<str>>(pub T, pub S);
+
+impl<T, S> RenderOnce for ProcessingInstruction<T, S>
+where
+    T: AsRef<str>,
+    S: AsRef<str>,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len() + self.1.as_ref().len() + 5
+    }
+}
+
+impl<T, S> RenderMut for ProcessingInstruction<T, S>
+where
+    T: AsRef<str>,
+    S: AsRef<str>,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        self.render(tmpl)
+    }
+}
+
+impl<T, S> Render for ProcessingInstruction<T, S>
+where
+    T: AsRef<str>,
+    S: AsRef<str>,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let (target, data) = (self.0.as_ref(), self.1.as_ref());
+        if !is_valid_tag_name(target) || target.eq_ignore_ascii_case("xml") {
+            tmpl.record_error("invalid processing instruction target");
+        } else if data.contains("?>") {
+            tmpl.record_error("processing instruction data contains `?>`");
+        } else {
+            tmpl.start_markup();
+            tmpl.write_raw("<?");
+            tmpl.write_raw(target);
+            if !data.is_empty() {
+                tmpl.write_raw(" ");
+                tmpl.write_raw(data);
+            }
+            tmpl.write_raw("?>");
+        }
+    }
+}
+
+/// A set of attributes built at runtime.
+///
+/// Spread them into a tag with `..`, alongside any statically written attributes. Values are
+/// escaped just like statically written attribute values (including the URL handling of `href`,
+/// `src`, etc.).
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{Attrs, Template};
+/// # fn main() {
+/// let mut extra = Attrs::new();
+/// extra
+///     .insert("class", "btn \"primary\"")
+///     .insert_optional("disabled", true)
+///     .insert_optional("title", None::<&str>);
+/// let page = html! { button(..&extra, id="x") : "Go" };
+/// assert_eq!(
+///     page.into_string().unwrap(),
+///     "<button class=\"btn &quot;primary&quot;\" disabled id=\"x\">Go</button>"
+/// );
+/// # }
+/// ```
+///
+/// FEATURE: requires "alloc".
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attrs {
+    attrs: Vec<(String, Option<String>)>,
+}
+
+#[cfg(feature = "alloc")]
+impl Attrs {
+    /// Create an empty set of attributes.
+    pub fn new() -> Self {
+        Attrs::default()
+    }
+
+    /// Set an attribute, replacing any existing attribute with the same name.
+    pub fn insert<N, V>(&mut self, name: N, value: V) -> &mut Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.set(name.into(), Some(value.into()))
+    }
+
+    /// Set an attribute the way `name ?= value` does: `Some(value)` sets the value, `true` sets
+    /// the attribute without a value, and `None` and `false` are skipped.
+    pub fn insert_optional<N, V>(&mut self, name: N, value: V) -> &mut Self
+    where
+        N: Into<String>,
+        V: crate::BoolOption,
+        V::Value: Into<String>,
+    {
+        match value.bool_option() {
+            (_, None) => self,
+            (true, Some(_)) => self.set(name.into(), None),
+            (false, Some(v)) => self.set(name.into(), Some(v.into())),
+        }
+    }
+
+    /// Remove an attribute.
+    pub fn remove(&mut self, name: &str) -> &mut Self {
+        self.attrs.retain(|(n, _)| n != name);
+        self
+    }
+
+    /// Returns the number of attributes.
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    /// Returns true if there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+
+    fn set(&mut self, name: String, value: Option<String>) -> &mut Self {
+        match self.attrs.iter_mut().find(|(n, _)| *n == name) {
+            Some(attr) => attr.1 = value,
+            None => self.attrs.push((name, value)),
+        }
+        self
+    }
+
+    /// Used by `html!` to render `..attrs`.
+    #[doc(hidden)]
+    pub fn spread<D: Dialect, A: Borrow<Attrs>>(attrs: A, tmpl: &mut TemplateBuffer<'_>) {
+        let escaping = tmpl.set_escaping(D::ATTRIBUTE_ESCAPING);
+        for (name, value) in &attrs.borrow().attrs {
+            if !is_valid_attribute_name(name) {
+                tmpl.record_error("invalid attribute name");
+                continue;
+            }
+            tmpl.write_raw(" ");
+            tmpl.write_raw(name);
+            let value = match value {
+                Some(value) => value,
+                None if D::BOOLEAN_ATTRIBUTE_VALUES => name,
+                None => continue,
+            };
+            tmpl.write_raw("=\"");
+            tmpl.start_attribute_value(name);
+            if D::is_url_attribute(name) {
+                Url(value.as_str()).render_once(tmpl);
+            } else {
+                tmpl.write_str(value);
+            }
+            tmpl.end_attribute_value();
+            tmpl.write_raw("\"");
+        }
+        tmpl.set_escaping(escaping);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<N, V> Extend<(N, V)> for Attrs
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn extend<I: IntoIterator<Item = (N, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<N, V> FromIterator<(N, V)> for Attrs
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut attrs = Attrs::new();
+        attrs.extend(iter);
+        attrs
+    }
+}
+
+/// Check the XML namespace prefixes used by the inner template.
+///
+/// Every prefixed element and attribute name (e.g., `atom:link`, `xlink:href`) written by the
+/// inner template must be declared with an `xmlns:prefix` attribute on the same element or an
+/// ancestor (inside the inner template). Otherwise, an error is recorded (and, as with all
+/// errors, nothing more is written). The `xml` prefix is always declared.
+///
+/// Prefixed tag names must be written as string literals (`"atom:link"`).
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{CheckNamespaces, Template};
+/// # fn main() {
+/// let ok = CheckNamespaces(xml! {
+///     feed(xmlns = "http://www.w3.org/2005/Atom", xmlns:media = "http://search.yahoo.com/mrss/") {
+///         "media:thumbnail"(url = "a.png");
+///     }
+/// });
+/// assert!(ok.into_string().is_ok());
+///
+/// let bad = CheckNamespaces(xml! {
+///     feed { "media:thumbnail"(url = "a.png"); }
+/// });
+/// assert!(bad.into_string().is_err());
+/// # }
+/// ```
+///
+/// FEATURE: requires "alloc".
+#[cfg(feature = "alloc")]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct CheckNamespaces<R>(pub R);
+
+#[cfg(feature = "alloc")]
+impl<R> RenderOnce for CheckNamespaces<R>
+where
+    R: RenderOnce,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = tmpl.start_namespace_checks();
+        self.0.render_once(tmpl);
+        if started {
+            tmpl.stop_namespace_checks();
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> RenderMut for CheckNamespaces<R>
+where
+    R: RenderMut,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = tmpl.start_namespace_checks();
+        self.0.render_mut(tmpl);
+        if started {
+            tmpl.stop_namespace_checks();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> Render for CheckNamespaces<R>
+where
+    R: Render,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = tmpl.start_namespace_checks();
+        self.0.render(tmpl);
+        if started {
+            tmpl.stop_namespace_checks();
+        }
+    }
+}
+
+/// Check that the HTML written by the inner template is nested correctly (debug builds only).
+///
+/// This tracks the elements opened by templates (raw markup isn't checked) and records an error
+/// (and, as with all errors, stops writing) when the inner template breaks one of the common HTML5
+/// content model rules:
+///
+/// * Block elements (`div`, `p`, `ul`, etc.) can't appear inside elements that only accept
+///   phrasing content (`p`, `span`, headings, `button`, etc.).
+/// * Interactive elements (`a`, `button`, `input`, etc.) can't appear inside `a` or `button`.
+/// * Forms can't be nested.
+/// * List items, table rows and cells, options, etc. must be children of the right elements
+///   (`li` in `ul`, `td` in `tr`, etc.).
+///
+/// When compiled without debug assertions, this simply renders the inner template.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{CheckNesting, Template};
+/// # fn main() {
+/// let ok = CheckNesting(html! {
+///     ul { li { a(href = "/") : "Home" } }
+/// });
+/// assert!(ok.into_string().is_ok());
+///
+/// let bad = CheckNesting(html! {
+///     p { div : "not a paragraph" }
+/// });
+/// assert!(bad.into_string().is_err());
+/// # }
+/// ```
+///
+/// FEATURE: requires "alloc".
+#[cfg(feature = "alloc")]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct CheckNesting<R>(pub R);
+
+#[cfg(feature = "alloc")]
+impl<R> RenderOnce for CheckNesting<R>
+where
+    R: RenderOnce,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = cfg!(debug_assertions) && tmpl.start_nesting_checks();
+        self.0.render_once(tmpl);
+        if started {
+            tmpl.stop_nesting_checks();
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> RenderMut for CheckNesting<R>
+where
+    R: RenderMut,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = cfg!(debug_assertions) && tmpl.start_nesting_checks();
+        self.0.render_mut(tmpl);
+        if started {
+            tmpl.stop_nesting_checks();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> Render for CheckNesting<R>
+where
+    R: Render,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let started = cfg!(debug_assertions) && tmpl.start_nesting_checks();
+        self.0.render(tmpl);
+        if started {
+            tmpl.stop_nesting_checks();
+        }
+    }
+}
+
+/// Stop rendering the inner template at the first error.
+///
+/// By default, templates keep running after an error is recorded (nothing more is written but
+/// every expression is still evaluated). Inside `FailFast`, the first error aborts rendering:
+/// remaining expressions, closures, subtemplates and loop iterations are skipped and the
+/// `write_to_*` call returns as soon as possible. See `TemplateBuffer::is_aborted`.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{FailFast, Template};
+/// # fn main() {
+/// let mut calls = 0;
+/// let result = FailFast(html! {
+///     @ for i in 0..10 {
+///         |tmpl| {
+///             calls += 1;
+///             if i == 2 {
+///                 tmpl.record_error("failed");
+///             }
+///         }
+///     }
+/// })
+/// .into_string();
+/// assert!(result.is_err());
+/// assert_eq!(calls, 3);
+/// # }
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct FailFast<R>(pub R);
+
+impl<R> RenderOnce for FailFast<R>
+where
+    R: RenderOnce,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        let fail_fast = tmpl.set_fail_fast(true);
+        self.0.render_once(tmpl);
+        tmpl.set_fail_fast(fail_fast);
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<R> RenderMut for FailFast<R>
+where
+    R: RenderMut,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        let fail_fast = tmpl.set_fail_fast(true);
+        self.0.render_mut(tmpl);
+        tmpl.set_fail_fast(fail_fast);
+    }
+}
+
+impl<R> Render for FailFast<R>
+where
+    R: Render,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        let fail_fast = tmpl.set_fail_fast(true);
+        self.0.render(tmpl);
+        tmpl.set_fail_fast(fail_fast);
+    }
+}
+
+/// Render a fallback instead of the inner template if it fails.
+///
+/// The inner template is rendered into a scratch buffer. If it records any errors (or, with
+/// "std", panics), its output is discarded and the errors are passed to the fallback, a closure
+/// that returns the template to render instead. Errors handled this way aren't returned from the
+/// `write_to_*` call and their paths only include the elements inside the boundary.
+///
+/// ```
+/// # #[macro_use] extern crate horrorshow;
+/// # use horrorshow::{ErrorBoundary, Template};
+/// # fn main() {
+/// let widget = html! {
+///     div(class = "widget") {
+///         : "Weather: ";
+///         : Err::<&str, &str>("service unavailable");
+///     }
+/// };
+/// let mut errors = Vec::new();
+/// let page = html! {
+///     h1 : "Dashboard";
+///     : ErrorBoundary::new(widget, |e| {
+///         errors.extend(e.render.iter().map(|e| e.to_string()));
+///         html! { p(class = "placeholder") : "Not available." }
+///     });
+/// };
+/// assert_eq!(
+///     page.into_string().unwrap(),
+///     "<h1>Dashboard</h1><p class=\"placeholder\">Not available.</p>"
+/// );
+/// assert_eq!(errors, ["service unavailable"]);
+/// # }
+/// ```
+///
+/// Panics are still reported by the panic hook (printed to stderr by default) and, as with
+/// `std::panic::catch_unwind`, panics that abort the process can't be caught.
+///
+/// FEATURE: requires "alloc".
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+pub struct ErrorBoundary<R, F> {
+    content: R,
+    fallback: F,
+}
+
+#[cfg(feature = "alloc")]
+impl<R, F, T> ErrorBoundary<R, F>
+where
+    R: RenderOnce,
+    F: FnOnce(Error) -> T,
+    T: RenderOnce,
+{
+    /// Render `content`, or `fallback(errors)` if it fails.
+    pub fn new(content: R, fallback: F) -> Self {
+        ErrorBoundary {
+            content: content,
+            fallback: fallback,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R, F, T> RenderOnce for ErrorBoundary<R, F>
+where
+    R: RenderOnce,
+    F: FnOnce(Error) -> T,
+    T: RenderOnce,
+{
+    fn render_once(self, tmpl: &mut TemplateBuffer<'_>) {
+        if tmpl.is_aborted() {
+            return;
+        }
+        let ErrorBoundary { content, fallback } = self;
+        let size_hint = content.size_hint();
+        match tmpl.render_scratch(size_hint, |tmpl| content.render_once(tmpl)) {
+            Ok(output) => tmpl.write_raw(&output),
+            Err(e) => fallback(e).render_once(tmpl),
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        self.content.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R, F, T> RenderMut for ErrorBoundary<R, F>
+where
+    R: RenderMut,
+    F: FnMut(Error) -> T,
+    T: RenderOnce,
+{
+    fn render_mut(&mut self, tmpl: &mut TemplateBuffer<'_>) {
+        if tmpl.is_aborted() {
+            return;
+        }
+        let size_hint = self.content.size_hint();
+        let content = &mut self.content;
+        match tmpl.render_scratch(size_hint, |tmpl| content.render_mut(tmpl)) {
+            Ok(output) => tmpl.write_raw(&output),
+            Err(e) => (self.fallback)(e).render_once(tmpl),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R, F, T> Render for ErrorBoundary<R, F>
+where
+    R: Render,
+    F: Fn(Error) -> T,
+    T: RenderOnce,
+{
+    fn render(&self, tmpl: &mut TemplateBuffer<'_>) {
+        if tmpl.is_aborted() {
+            return;
+        }
+        let content = &self.content;
+        match tmpl.render_scratch(content.size_hint(), |tmpl| content.render(tmpl)) {
+            Ok(output) => tmpl.write_raw(&output),
+            Err(e) => (self.fallback)(e).render_once(tmpl),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+// rustfmt doesn't like this file (lines too long, too hard to fix)
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+/// Check tag and attribute names against HTML5 (the `strict-html` feature).
+///
+/// Only identifiers used in `html!` and `xhtml!` are checked: custom elements (names containing a
+/// `-`), `data-*` and `aria-*` attributes, and namespaced attributes (`xlink:href`) are always
+/// allowed and string literal names (`"my-tag"`) can be used to bypass the check entirely.
+/// Names must be lowercase (`DIV` is rejected).
+///
+/// Typos are compile errors:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// html! { dvi {} };
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate horrorshow;
+/// # fn main() {
+/// html! { img(scr = "a.png"); };
+/// # }
+/// ```
+///
+/// Don't call this manually.
+#[cfg(feature = "strict-html")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __strict_html {
+    (html $kind:ident $($name:tt)+) => { $crate::__strict_html!(@$kind $($name)+); };
+    (xhtml $kind:ident $($name:tt)+) => { $crate::__strict_html!(@$kind $($name)+); };
+    ($type:ident $kind:ident $($name:tt)+) => {};
+
+    //////// ELEMENTS
+    // Custom elements.
+    (@element $first:ident - $($rest:tt)+) => {};
+    (@element a) => {};
+    (@element abbr) => {};
+    (@element address) => {};
+    (@element article) => {};
+    (@element aside) => {};
+    (@element audio) => {};
+    (@element b) => {};
+    (@element bdi) => {};
+    (@element bdo) => {};
+    (@element blockquote) => {};
+    (@element body) => {};
+    (@element button) => {};
+    (@element canvas) => {};
+    (@element caption) => {};
+    (@element cite) => {};
+    (@element code) => {};
+    (@element colgroup) => {};
+    (@element data) => {};
+    (@element datalist) => {};
+    (@element dd) => {};
+    (@element del) => {};
+    (@element details) => {};
+    (@element dfn) => {};
+    (@element dialog) => {};
+    (@element div) => {};
+    (@element dl) => {};
+    (@element dt) => {};
+    (@element em) => {};
+    (@element fieldset) => {};
+    (@element figcaption) => {};
+    (@element figure) => {};
+    (@element footer) => {};
+    (@element form) => {};
+    (@element h1) => {};
+    (@element h2) => {};
+    (@element h3) => {};
+    (@element h4) => {};
+    (@element h5) => {};
+    (@element h6) => {};
+    (@element head) => {};
+    (@element header) => {};
+    (@element hgroup) => {};
+    (@element html) => {};
+    (@element i) => {};
+    (@element iframe) => {};
+    (@element ins) => {};
+    (@element kbd) => {};
+    (@element label) => {};
+    (@element legend) => {};
+    (@element li) => {};
+    (@element main) => {};
+    (@element map) => {};
+    (@element mark) => {};
+    (@element math) => {};
+    (@element menu) => {};
+    (@element meter) => {};
+    (@element nav) => {};
+    (@element noscript) => {};
+    (@element object) => {};
+    (@element ol) => {};
+    (@element optgroup) => {};
+    (@element option) => {};
+    (@element output) => {};
+    (@element p) => {};
+    (@element picture) => {};
+    (@element pre) => {};
+    (@element progress) => {};
+    (@element q) => {};
+    (@element rp) => {};
+    (@element rt) => {};
+    (@element ruby) => {};
+    (@element s) => {};
+    (@element samp) => {};
+    (@element script) => {};
+    (@element search) => {};
+    (@element section) => {};
+    (@element select) => {};
+    (@element slot) => {};
+    (@element small) => {};
+    (@element span) => {};
+    (@element strong) => {};
+    (@element style) => {};
+    (@element sub) => {};
+    (@element summary) => {};
+    (@element sup) => {};
+    (@element svg) => {};
+    (@element table) => {};
+    (@element tbody) => {};
+    (@element td) => {};
+    (@element template) => {};
+    (@element textarea) => {};
+    (@element tfoot) => {};
+    (@element th) => {};
+    (@element thead) => {};
+    (@element time) => {};
+    (@element title) => {};
+    (@element tr) => {};
+    (@element u) => {};
+    (@element ul) => {};
+    (@element var) => {};
+    (@element video) => {};
+    // Void elements (see `VOID_ELEMENTS`).
+    (@element $($name:tt)+) => { $crate::__void_tag!(@strict $($name)+); };
+    (@unknown_element $($name:tt)+) => {
+        compile_error!(concat!("unknown HTML element: ", $(stringify!($name)),+,
+                               "\n\nUse a string literal (\"name\") to bypass the `strict-html` check."));
+    };
+
+    //////// ATTRIBUTES
+    (@attribute data - $($rest:tt)+) => {};
+    (@attribute aria - $($rest:tt)+) => {};
+    // Namespaced attributes (`xml:lang`, `xlink:href`, ...).
+    (@attribute $($first:ident)-+ : $($rest:tt)+) => {};
+    // Global attributes.
+    (@attribute accesskey) => {};
+    (@attribute autocapitalize) => {};
+    (@attribute autofocus) => {};
+    (@attribute class) => {};
+    (@attribute contenteditable) => {};
+    (@attribute dir) => {};
+    (@attribute draggable) => {};
+    (@attribute enterkeyhint) => {};
+    (@attribute hidden) => {};
+    (@attribute id) => {};
+    (@attribute inert) => {};
+    (@attribute inputmode) => {};
+    (@attribute is) => {};
+    (@attribute itemid) => {};
+    (@attribute itemprop) => {};
+    (@attribute itemref) => {};
+    (@attribute itemscope) => {};
+    (@attribute itemtype) => {};
+    (@attribute lang) => {};
+    (@attribute nonce) => {};
+    (@attribute popover) => {};
+    (@attribute role) => {};
+    (@attribute slot) => {};
+    (@attribute spellcheck) => {};
+    (@attribute style) => {};
+    (@attribute tabindex) => {};
+    (@attribute title) => {};
+    (@attribute translate) => {};
+    (@attribute xmlns) => {};
+    // Element specific attributes.
+    (@attribute accept) => {};
+    (@attribute accept - charset) => {};
+    (@attribute action) => {};
+    (@attribute allow) => {};
+    (@attribute allowfullscreen) => {};
+    (@attribute alt) => {};
+    (@attribute as) => {};
+    (@attribute async) => {};
+    (@attribute autocomplete) => {};
+    (@attribute autoplay) => {};
+    (@attribute blocking) => {};
+    (@attribute charset) => {};
+    (@attribute checked) => {};
+    (@attribute cite) => {};
+    (@attribute cols) => {};
+    (@attribute colspan) => {};
+    (@attribute content) => {};
+    (@attribute controls) => {};
+    (@attribute coords) => {};
+    (@attribute crossorigin) => {};
+    (@attribute data) => {};
+    (@attribute datetime) => {};
+    (@attribute decoding) => {};
+    (@attribute default) => {};
+    (@attribute defer) => {};
+    (@attribute dirname) => {};
+    (@attribute disabled) => {};
+    (@attribute download) => {};
+    (@attribute enctype) => {};
+    (@attribute fetchpriority) => {};
+    (@attribute for) => {};
+    (@attribute form) => {};
+    (@attribute formaction) => {};
+    (@attribute formenctype) => {};
+    (@attribute formmethod) => {};
+    (@attribute formnovalidate) => {};
+    (@attribute formtarget) => {};
+    (@attribute headers) => {};
+    (@attribute height) => {};
+    (@attribute high) => {};
+    (@attribute href) => {};
+    (@attribute hreflang) => {};
+    (@attribute http - equiv) => {};
+    (@attribute imagesizes) => {};
+    (@attribute imagesrcset) => {};
+    (@attribute integrity) => {};
+    (@attribute ismap) => {};
+    (@attribute kind) => {};
+    (@attribute label) => {};
+    (@attribute list) => {};
+    (@attribute loading) => {};
+    (@attribute loop) => {};
+    (@attribute low) => {};
+    (@attribute max) => {};
+    (@attribute maxlength) => {};
+    (@attribute media) => {};
+    (@attribute method) => {};
+    (@attribute min) => {};
+    (@attribute minlength) => {};
+    (@attribute multiple) => {};
+    (@attribute muted) => {};
+    (@attribute name) => {};
+    (@attribute nomodule) => {};
+    (@attribute novalidate) => {};
+    (@attribute open) => {};
+    (@attribute optimum) => {};
+    (@attribute pattern) => {};
+    (@attribute ping) => {};
+    (@attribute placeholder) => {};
+    (@attribute playsinline) => {};
+    (@attribute popovertarget) => {};
+    (@attribute popovertargetaction) => {};
+    (@attribute poster) => {};
+    (@attribute preload) => {};
+    (@attribute readonly) => {};
+    (@attribute referrerpolicy) => {};
+    (@attribute rel) => {};
+    (@attribute required) => {};
+    (@attribute reversed) => {};
+    (@attribute rows) => {};
+    (@attribute rowspan) => {};
+    (@attribute sandbox) => {};
+    (@attribute scope) => {};
+    (@attribute selected) => {};
+    (@attribute shadowrootmode) => {};
+    (@attribute shape) => {};
+    (@attribute size) => {};
+    (@attribute sizes) => {};
+    (@attribute span) => {};
+    (@attribute src) => {};
+    (@attribute srcdoc) => {};
+    (@attribute srclang) => {};
+    (@attribute srcset) => {};
+    (@attribute start) => {};
+    (@attribute step) => {};
+    (@attribute target) => {};
+    (@attribute type) => {};
+    (@attribute usemap) => {};
+    (@attribute value) => {};
+    (@attribute width) => {};
+    (@attribute wrap) => {};
+    // Event handlers.
+    (@attribute onabort) => {};
+    (@attribute onafterprint) => {};
+    (@attribute onanimationend) => {};
+    (@attribute onbeforeprint) => {};
+    (@attribute onbeforeunload) => {};
+    (@attribute onblur) => {};
+    (@attribute oncancel) => {};
+    (@attribute oncanplay) => {};
+    (@attribute onchange) => {};
+    (@attribute onclick) => {};
+    (@attribute onclose) => {};
+    (@attribute oncontextmenu) => {};
+    (@attribute oncopy) => {};
+    (@attribute oncut) => {};
+    (@attribute ondblclick) => {};
+    (@attribute ondrag) => {};
+    (@attribute ondragend) => {};
+    (@attribute ondragenter) => {};
+    (@attribute ondragleave) => {};
+    (@attribute ondragover) => {};
+    (@attribute ondragstart) => {};
+    (@attribute ondrop) => {};
+    (@attribute onended) => {};
+    (@attribute onerror) => {};
+    (@attribute onfocus) => {};
+    (@attribute onhashchange) => {};
+    (@attribute oninput) => {};
+    (@attribute oninvalid) => {};
+    (@attribute onkeydown) => {};
+    (@attribute onkeypress) => {};
+    (@attribute onkeyup) => {};
+    (@attribute onload) => {};
+    (@attribute onmessage) => {};
+    (@attribute onmousedown) => {};
+    (@attribute onmouseenter) => {};
+    (@attribute onmouseleave) => {};
+    (@attribute onmousemove) => {};
+    (@attribute onmouseout) => {};
+    (@attribute onmouseover) => {};
+    (@attribute onmouseup) => {};
+    (@attribute onpaste) => {};
+    (@attribute onpause) => {};
+    (@attribute onplay) => {};
+    (@attribute onpointerdown) => {};
+    (@attribute onpointermove) => {};
+    (@attribute onpointerup) => {};
+    (@attribute onpopstate) => {};
+    (@attribute onreset) => {};
+    (@attribute onresize) => {};
+    (@attribute onscroll) => {};
+    (@attribute onselect) => {};
+    (@attribute onsubmit) => {};
+    (@attribute ontimeupdate) => {};
+    (@attribute ontoggle) => {};
+    (@attribute ontouchend) => {};
+    (@attribute ontouchmove) => {};
+    (@attribute ontouchstart) => {};
+    (@attribute ontransitionend) => {};
+    (@attribute onunload) => {};
+    (@attribute onvolumechange) => {};
+    (@attribute onwheel) => {};
+    (@attribute $($name:tt)+) => {
+        compile_error!(concat!("unknown HTML attribute: ", $(stringify!($name)),+,
+                               "\n\nUse a string literal (\"name\") to bypass the `strict-html` check."));
+    };
+}
+
+#[cfg(not(feature = "strict-html"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __strict_html {
+    ($($tt:tt)*) => {};
+}
@@ -0,0 +1,209 @@
+use core::marker::PhantomData;
+
+use crate::dialect::Dialect;
+use crate::template::{Escaping, TemplateBuffer};
+
+/// Defines `VOID_ELEMENTS` (used by the `Html` and `Xhtml` dialects) and the `__void_tag!` macro
+/// (used by `html!` and `xhtml!` to close elements at compile time) from one list so they can't
+/// disagree. `$d` is a literal `$` (for the metavariables of the generated macro).
+macro_rules! void_elements {
+    ($d:tt $($tag:ident $name:tt,)*) => {
+        /// HTML elements that never have a closing tag.
+        pub(crate) const VOID_ELEMENTS: &[&str] = &[$($name),*];
+
+        /// Expands to `$close` if the tag is a void element and to its end tag (`"></tag>"`)
+        /// otherwise. `@strict` accepts void elements and forwards everything else to the
+        /// `strict-html` check. Don't call this manually.
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! __void_tag {
+            $(
+                ($d close:tt, $tag) => { $d close };
+                ($d close:tt, $name) => { $d close };
+                (@strict $tag) => {};
+            )*
+            ($d close:tt, $d ($d tag:ident)-+) => {
+                concat!("></", $crate::append_html!(@stringify_compressed $d ($d tag)-+), ">")
+            };
+            ($d close:tt, $d tag:tt) => {
+                concat!("></", $d tag, ">")
+            };
+            (@strict $d ($d name:tt)+) => {
+                $crate::__strict_html!(@unknown_element $d ($d name)+);
+            };
+        }
+    };
+}
+
+void_elements! {
+    $
+    area "area",
+    base "base",
+    br "br",
+    col "col",
+    embed "embed",
+    hr "hr",
+    img "img",
+    input "input",
+    link "link",
+    meta "meta",
+    param "param",
+    source "source",
+    track "track",
+    wbr "wbr",
+}
+
+/// HTML attributes that contain URLs (and are therefore rendered through `Url`).
+///
+/// Attributes are matched by name alone, so `data` (a URL only on `object`) isn't included.
+pub(crate) const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "formaction",
+    "href",
+    "icon",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "usemap",
+    "xlink:href",
+];
+
+/// Returns true if `name` can be written as a tag name without breaking the surrounding markup.
+///
+/// Tag names must start with an ASCII letter and may only contain ASCII letters, digits, `-`, `_`,
+/// `.`, `:`, and non-ASCII characters.
+///
+/// This is a `const fn` so that string literal tag names can be checked at compile time.
+#[doc(hidden)]
+pub const fn is_valid_tag_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() || !bytes[0].is_ascii_alphabetic() {
+        return false;
+    }
+    let mut i = 1;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if !(b.is_ascii_alphanumeric()
+            || b == b'-'
+            || b == b'_'
+            || b == b'.'
+            || b == b':'
+            || !b.is_ascii())
+        {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns true if `name` can be written as an attribute name without breaking the surrounding
+/// markup.
+///
+/// Attribute names may contain anything but whitespace, control characters, quotes, `<`, `>`, `/`,
+/// `=`, `&`, and `` ` ``. This allows names like `@click` and `x-on:submit.prevent`.
+#[doc(hidden)]
+pub const fn is_valid_attribute_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            0..=b' ' | 0x7f | b'"' | b'\'' | b'<' | b'>' | b'/' | b'=' | b'&' | b'`' => {
+                return false
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A tag with a name that is only known at runtime (`@tag(name) { ... }`).
+///
+/// Invalid names are recorded as errors (which stops all further output) and are never written.
+#[doc(hidden)]
+pub struct DynamicTag<'n, D> {
+    name: &'n str,
+    valid: bool,
+    dialect: PhantomData<D>,
+}
+
+impl<'n, D: Dialect> DynamicTag<'n, D> {
+    pub fn new<N: AsRef<str> + ?Sized>(tmpl: &mut TemplateBuffer<'_>, name: &'n N) -> Self {
+        let name = name.as_ref();
+        let valid = is_valid_tag_name(name);
+        if valid {
+            tmpl.open_dynamic_element(name);
+        } else {
+            tmpl.record_error("invalid tag name");
+        }
+        DynamicTag {
+            name: name,
+            valid: valid,
+            dialect: PhantomData,
+        }
+    }
+
+    /// Write the start of the start tag (everything up to the attributes).
+    pub fn open(&self, tmpl: &mut TemplateBuffer<'_>) {
+        if self.valid {
+            tmpl.write_raw("<");
+            tmpl.write_raw(self.name);
+        }
+    }
+
+    /// Finish the start tag (of a tag with children) and switch escaping modes as needed. Returns
+    /// the escaping mode to restore in `close`.
+    ///
+    /// Records an error if this is a void element (which can't have children).
+    pub fn open_end(&self, tmpl: &mut TemplateBuffer<'_>) -> Option<Escaping> {
+        if !self.valid {
+            return None;
+        }
+        if D::is_void(self.name) {
+            tmpl.record_error("void elements can't have children");
+            return None;
+        }
+        tmpl.write_raw(">");
+        D::content_escaping(self.name).map(|e| tmpl.set_escaping(e))
+    }
+
+    /// Write the end tag.
+    pub fn close(&self, tmpl: &mut TemplateBuffer<'_>, escaping: Option<Escaping>) {
+        if let Some(e) = escaping {
+            tmpl.set_escaping(e);
+        }
+        if self.valid {
+            tmpl.close_dynamic_element(self.name);
+            tmpl.write_raw("</");
+            tmpl.write_raw(self.name);
+            tmpl.write_raw(">");
+        }
+    }
+
+    /// Finish a tag without children.
+    pub fn close_empty(&self, tmpl: &mut TemplateBuffer<'_>) {
+        if !self.valid {
+            return;
+        }
+        match D::empty_tag_end(self.name) {
+            Some(end) => {
+                tmpl.close_empty_element(self.name, end);
+                tmpl.write_raw(end);
+            }
+            None => {
+                tmpl.close_dynamic_element(self.name);
+                tmpl.write_raw("></");
+                tmpl.write_raw(self.name);
+                tmpl.write_raw(">");
+            }
+        }
+    }
+}
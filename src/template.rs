@@ -1,12 +1,20 @@
 use core::fmt;
 
 #[cfg(feature = "alloc")]
-use alloc::string::String;
+use alloc::{boxed::Box, string::String, vec::Vec};
 
 #[cfg(feature = "std")]
 use std::io;
 
-use crate::error::{self, Error};
+#[cfg(feature = "alloc")]
+use crate::error::RenderError;
+use crate::error::{self, Error, Location};
+#[cfg(feature = "alloc")]
+use crate::namespaces::NamespaceChecker;
+#[cfg(feature = "alloc")]
+use crate::nesting::NestingChecker;
+#[cfg(feature = "alloc")]
+use crate::pretty::PrettyWriter;
 use crate::render::RenderOnce;
 
 /// A template that can be rendered into something.
@@ -32,10 +40,87 @@ pub trait Template: RenderOnce + Sized {
     /// FEATURE: requires "alloc".
     #[cfg(feature = "alloc")]
     fn write_to_string(self, string: &mut String) -> Result<(), Error> {
-        let mut buffer = TemplateBuffer {
-            writer: InnerTemplateWriter::Str(string),
-            error: Default::default(),
-        };
+        let mut buffer = TemplateBuffer::new(InnerTemplateWriter::Str(string));
+        self.render_once(&mut buffer);
+        buffer.into_result()
+    }
+
+    /// Render this into a new byte vector (e.g., for an HTTP response body).
+    ///
+    /// This is as fast as `into_string` (and much faster than `write_to_io`).
+    ///
+    /// FEATURE: requires "alloc".
+    #[cfg(feature = "alloc")]
+    fn into_bytes(self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::with_capacity(self.size_hint());
+        self.write_to_vec(&mut bytes)?;
+        bytes.shrink_to_fit();
+        Ok(bytes)
+    }
+
+    /// Render this into an existing byte vector (as UTF-8).
+    ///
+    /// FEATURE: requires "alloc".
+    #[cfg(feature = "alloc")]
+    fn write_to_vec(self, bytes: &mut Vec<u8>) -> Result<(), Error> {
+        let mut buffer = TemplateBuffer::new(InnerTemplateWriter::Bytes(bytes));
+        self.render_once(&mut buffer);
+        buffer.into_result()
+    }
+
+    /// Render this into a new String, pretty-printed.
+    ///
+    /// See `write_to_string_pretty`.
+    ///
+    /// FEATURE: requires "alloc".
+    #[cfg(feature = "alloc")]
+    fn into_string_pretty(self) -> Result<String, Error> {
+        let mut string = String::with_capacity(self.size_hint());
+        self.write_to_string_pretty(&mut string)?;
+        string.shrink_to_fit();
+        Ok(string)
+    }
+
+    /// Render this into an existing String, pretty-printed.
+    ///
+    /// Block elements, comments and processing instructions are placed on their own line and
+    /// indented by their depth. End tags are placed on their own line when the element contains
+    /// block elements. Inline elements (`a`, `b`, `span`, `img`, ...) stay where they are and the
+    /// contents of inline and whitespace sensitive elements (`pre`, `textarea`, `script` and
+    /// `style`) are left untouched, so whitespace is only added next to block elements. Only the
+    /// elements written by templates are laid out: raw markup (e.g., `Raw`) is written as-is.
+    ///
+    /// This is intended for debugging: whitespace next to block elements is usually ignored by
+    /// browsers but the inserted whitespace can still change how the document renders (e.g., in
+    /// elements styled with `white-space: pre`).
+    ///
+    /// ```
+    /// # #[macro_use] extern crate horrorshow;
+    /// # use horrorshow::Template;
+    /// # fn main() {
+    /// let list = html! {
+    ///     ul {
+    ///         li : "one";
+    ///         li { p { : "two "; b : "three" } }
+    ///     }
+    /// };
+    /// let mut s = String::new();
+    /// list.write_to_string_pretty(&mut s).unwrap();
+    /// assert_eq!(s, "\
+    /// <ul>
+    ///   <li>one</li>
+    ///   <li>
+    ///     <p>two <b>three</b></p>
+    ///   </li>
+    /// </ul>");
+    /// # }
+    /// ```
+    ///
+    /// FEATURE: requires "alloc".
+    #[cfg(feature = "alloc")]
+    fn write_to_string_pretty(self, string: &mut String) -> Result<(), Error> {
+        let mut buffer =
+            TemplateBuffer::new(InnerTemplateWriter::Pretty(PrettyWriter::new(string)));
         self.render_once(&mut buffer);
         buffer.into_result()
     }
@@ -44,10 +129,7 @@ pub trait Template: RenderOnce + Sized {
     ///
     /// FnRenderer also implements Display but that's about twice as slow...
     fn write_to_fmt(self, writer: &mut dyn fmt::Write) -> Result<(), Error> {
-        let mut buffer = TemplateBuffer {
-            writer: InnerTemplateWriter::Fmt(writer),
-            error: Default::default(),
-        };
+        let mut buffer = TemplateBuffer::new(InnerTemplateWriter::Fmt(writer));
         self.render_once(&mut buffer);
         buffer.into_result()
     }
@@ -61,10 +143,7 @@ pub trait Template: RenderOnce + Sized {
     /// FEATURE: requires "std".
     #[cfg(feature = "std")]
     fn write_to_io(self, writer: &mut dyn io::Write) -> Result<(), Error> {
-        let mut buffer = TemplateBuffer {
-            writer: InnerTemplateWriter::Io(writer),
-            error: Default::default(),
-        };
+        let mut buffer = TemplateBuffer::new(InnerTemplateWriter::Io(writer));
         self.render_once(&mut buffer);
         buffer.into_result()
     }
@@ -72,6 +151,42 @@ pub trait Template: RenderOnce + Sized {
 
 impl<T: RenderOnce + Sized> Template for T {}
 
+/// The escaping rules applied to text written through the escaping methods of a
+/// `TemplateBuffer` (`write_str`, `write_fmt`, `as_writer`).
+///
+/// `html!` uses `Html` by default, switching to `HtmlAttribute` for attribute values and `Script`
+/// and `Style` inside `script` and `style` elements. `xml!` and `xhtml!` use `Xml`. Raw writes are
+/// never escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Escaping {
+    /// HTML text: `&`, `"`, `<` and `>` are replaced with character references.
+    Html,
+    /// HTML attribute values: like `Html` but also escapes `'` (as `&#39;`) so values are safe in
+    /// both single and double quoted attributes. `html!` uses this for all attribute values.
+    HtmlAttribute,
+    /// Strict XML 1.0: `&`, `"`, `'`, `<` and `>` are replaced with the predefined XML entities and
+    /// characters that may not appear in XML documents (control characters other than tab, newline
+    /// and carriage return, U+FFFE and U+FFFF) are replaced with U+FFFD. `xml!` and `xhtml!` use
+    /// this everywhere.
+    Xml,
+    /// The body of a `<script>` element. Character references aren't decoded here so text is
+    /// written as-is, except that `</` and `<!` are broken up (`<\/`, `<\!`) to prevent
+    /// interpolated values from closing the element or opening an HTML comment. Inside a
+    /// javascript string literal, these escapes decode to the original text. This doesn't stop
+    /// values from ending a string literal: interpolate values with `Json` instead.
+    Script,
+    /// The body of a `<style>` element. Escaped exactly like `Script` (`\/` and `\!` are valid
+    /// CSS escapes).
+    Style,
+}
+
+impl Default for Escaping {
+    #[inline]
+    fn default() -> Self {
+        Escaping::Html
+    }
+}
+
 /// A template buffer. This is the type that gets passed to closures inside templates.
 ///
 /// Example:
@@ -87,17 +202,65 @@ impl<T: RenderOnce + Sized> Template for T {}
 pub struct TemplateBuffer<'a> {
     writer: InnerTemplateWriter<'a>,
     error: Error,
+    escaping: Escaping,
+    /// The last escaped write ended in a `<` (only tracked in `Script`/`Style` mode).
+    script_lt: bool,
+    /// Stop rendering at the first error (see `FailFast`).
+    fail_fast: bool,
+    /// The number of open elements.
+    #[cfg(feature = "alloc")]
+    depth: usize,
+    /// Recorded errors (indices into `error.render`) that are still inside open elements, and
+    /// the depth of the innermost open element that hasn't yet been added to their paths.
+    ///
+    /// Paths are collected as elements are closed so that the (common) error-free case doesn't
+    /// have to keep track of the element names.
+    #[cfg(feature = "alloc")]
+    unresolved: Vec<(usize, usize)>,
+    /// The innermost template being rendered (attached to recorded errors).
+    #[cfg(feature = "alloc")]
+    location: Option<Location>,
+    /// The namespace prefixes in scope (only tracked inside `CheckNamespaces`).
+    #[cfg(feature = "alloc")]
+    namespaces: Option<Box<NamespaceChecker>>,
+    /// The open HTML elements (only tracked inside `CheckNesting`).
+    #[cfg(feature = "alloc")]
+    nesting: Option<Box<NestingChecker>>,
 }
 
 enum InnerTemplateWriter<'a> {
     Fmt(&'a mut dyn fmt::Write),
     #[cfg(feature = "alloc")]
     Str(&'a mut String),
+    #[cfg(feature = "alloc")]
+    Bytes(&'a mut Vec<u8>),
+    #[cfg(feature = "alloc")]
+    Pretty(PrettyWriter<'a>),
     #[cfg(feature = "std")]
     Io(&'a mut dyn io::Write),
 }
 
 impl<'a> TemplateBuffer<'a> {
+    fn new(writer: InnerTemplateWriter<'a>) -> Self {
+        TemplateBuffer {
+            writer: writer,
+            error: Default::default(),
+            escaping: Escaping::Html,
+            script_lt: false,
+            fail_fast: false,
+            #[cfg(feature = "alloc")]
+            depth: 0,
+            #[cfg(feature = "alloc")]
+            unresolved: Vec::new(),
+            #[cfg(feature = "alloc")]
+            location: None,
+            #[cfg(feature = "alloc")]
+            namespaces: None,
+            #[cfg(feature = "alloc")]
+            nesting: None,
+        }
+    }
+
     /// Record an error. If a template calls this function, rendering will be
     /// short-circuited and the error will be returned to the user.
     ///
@@ -110,13 +273,206 @@ impl<'a> TemplateBuffer<'a> {
     #[cold]
     #[cfg(feature = "std")]
     pub fn record_error<E: Into<Box<dyn std::error::Error + Send + Sync>>>(&mut self, e: E) {
-        self.error.render.push(e.into());
+        let error = self.render_error(e.into());
+        self.push_render_error(error);
     }
 
     #[cold]
     #[cfg(all(not(feature = "std"), feature = "alloc"))]
     pub fn record_error<E: alloc::string::ToString>(&mut self, e: E) {
-        self.error.render.push(e.to_string());
+        let error = self.render_error(e.to_string());
+        self.push_render_error(error);
+    }
+
+    /// Attach the current location to an error. Its path is filled in as the open elements are
+    /// closed (see `push_render_error`).
+    #[cfg(feature = "alloc")]
+    fn render_error(
+        &self,
+        #[cfg(feature = "std")] source: Box<dyn std::error::Error + Send + Sync>,
+        #[cfg(not(feature = "std"))] source: String,
+    ) -> RenderError {
+        RenderError {
+            path: Vec::new(),
+            location: self.location,
+            source: source,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn push_render_error(&mut self, error: RenderError) {
+        if self.depth > 0 {
+            self.unresolved.push((self.error.render.len(), self.depth));
+        }
+        self.error.render.push(error);
+    }
+
+    /// Add the element being closed to the paths of the errors recorded inside it.
+    #[cold]
+    #[cfg(feature = "alloc")]
+    fn resolve_paths(&mut self, name: &str) {
+        let render = &mut self.error.render;
+        let depth = self.depth;
+        for &mut (i, ref mut level) in &mut self.unresolved {
+            if *level == depth {
+                render[i].path.insert(0, String::from(name));
+                *level -= 1;
+            }
+        }
+        self.unresolved.retain(|&(_, level)| level > 0);
+    }
+
+    /// Enter the template defined at `location`. Returns the location to restore with
+    /// `leave_template`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn enter_template(&mut self, location: Location) -> Option<Location> {
+        #[cfg(feature = "alloc")]
+        {
+            self.location.replace(location)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = location;
+            None
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn leave_template(&mut self, _previous: Option<Location>) {
+        #[cfg(feature = "alloc")]
+        {
+            self.location = _previous;
+        }
+    }
+
+    /// Record that an element is about to be opened (for error reporting, `CheckNesting` and
+    /// pretty-printing).
+    #[doc(hidden)]
+    #[inline]
+    pub fn open_element(&mut self, name: &'static str) {
+        self.open_dynamic_element(name);
+    }
+
+    /// Like `open_element` but for names only known at runtime.
+    #[doc(hidden)]
+    #[inline]
+    pub fn open_dynamic_element(&mut self, _name: &str) {
+        #[cfg(feature = "alloc")]
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.nesting.is_some() {
+                    self.check_nesting(|nesting| nesting.open(_name));
+                }
+            }
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                writer.open(_name);
+            }
+            self.depth += 1;
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn close_element(&mut self, name: &'static str) {
+        self.close_dynamic_element(name);
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn close_dynamic_element(&mut self, _name: &str) {
+        self.end_element(_name);
+        #[cfg(feature = "alloc")]
+        {
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                writer.close(true);
+            }
+        }
+    }
+
+    /// Like `close_dynamic_element` but for elements closed by finishing the start tag with
+    /// `end` (`>`, `/>`, or `></name>`).
+    #[doc(hidden)]
+    #[inline]
+    pub fn close_empty_element(&mut self, _name: &str, _end: &str) {
+        self.end_element(_name);
+        #[cfg(feature = "alloc")]
+        {
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                writer.close(_end.contains('<'));
+            }
+        }
+    }
+
+    #[inline]
+    fn end_element(&mut self, _name: &str) {
+        #[cfg(feature = "alloc")]
+        {
+            if !self.unresolved.is_empty() {
+                self.resolve_paths(_name);
+            }
+            self.depth -= 1;
+            #[cfg(debug_assertions)]
+            {
+                if self.nesting.is_some() {
+                    self.check_nesting(|nesting| nesting.close());
+                }
+            }
+        }
+    }
+
+    /// Record an element without attributes or children, written as `<name` followed by `end`
+    /// (these are written as constants and don't need to be opened or closed).
+    #[doc(hidden)]
+    #[inline]
+    pub fn empty_element(&mut self, _name: &'static str, _end: &'static str) {
+        #[cfg(feature = "alloc")]
+        {
+            #[cfg(debug_assertions)]
+            {
+                if self.nesting.is_some() {
+                    self.check_nesting(|nesting| nesting.empty(_name));
+                }
+            }
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                writer.open(_name);
+                writer.close(_end.contains('<'));
+            }
+        }
+    }
+
+    /// Called before writing a comment or a processing instruction (for pretty-printing).
+    #[inline]
+    pub(crate) fn start_markup(&mut self) {
+        #[cfg(feature = "alloc")]
+        {
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                writer.markup();
+            }
+        }
+    }
+
+    /// Called before writing the value of the attribute `name` (see `CheckNesting`).
+    #[inline]
+    pub(crate) fn start_attribute_value(&mut self, _name: &str) {
+        #[cfg(all(feature = "alloc", debug_assertions))]
+        {
+            if let Some(ref mut nesting) = self.nesting {
+                nesting.start_attribute_value(_name);
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn end_attribute_value(&mut self) {
+        #[cfg(all(feature = "alloc", debug_assertions))]
+        {
+            if let Some(ref mut nesting) = self.nesting {
+                nesting.end_attribute_value();
+            }
+        }
     }
 
     #[cold]
@@ -127,6 +483,87 @@ impl<'a> TemplateBuffer<'a> {
         }
     }
 
+    /// Returns true if rendering has been aborted: an error has been recorded (or writing failed)
+    /// inside `FailFast`.
+    ///
+    /// Templates stop evaluating expressions, closures and loop iterations once this returns
+    /// true. Manual `Render` implementations can check it to skip expensive work.
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        self.fail_fast && !error::is_empty(&self.error)
+    }
+
+    /// Enable or disable fail-fast mode, returning the previous setting.
+    pub(crate) fn set_fail_fast(&mut self, fail_fast: bool) -> bool {
+        core::mem::replace(&mut self.fail_fast, fail_fast)
+    }
+
+    /// Render into a scratch buffer (see `ErrorBoundary`), returning either the output or the
+    /// errors recorded while rendering it. With "std", panics are caught and recorded as errors.
+    ///
+    /// The scratch buffer inherits the escaping state, fail-fast setting, location, nesting
+    /// checker, and pretty-printing layout of this buffer but not the open elements (error paths
+    /// start at the scratch buffer) or the namespace checker (the output is checked when it's
+    /// written to this buffer).
+    #[cfg(feature = "alloc")]
+    pub(crate) fn render_scratch<F>(&mut self, size_hint: usize, f: F) -> Result<String, Error>
+    where
+        F: FnOnce(&mut TemplateBuffer<'_>),
+    {
+        let mut output = String::with_capacity(size_hint);
+        let result = {
+            let writer = match self.writer {
+                InnerTemplateWriter::Pretty(ref writer) => {
+                    InnerTemplateWriter::Pretty(writer.fork(&mut output))
+                }
+                _ => InnerTemplateWriter::Str(&mut output),
+            };
+            let mut scratch = TemplateBuffer::new(writer);
+            scratch.escaping = self.escaping;
+            scratch.script_lt = self.script_lt;
+            scratch.fail_fast = self.fail_fast;
+            scratch.location = self.location;
+            scratch.nesting = self.nesting.clone();
+            #[cfg(feature = "std")]
+            {
+                use std::panic::{catch_unwind, AssertUnwindSafe};
+                if let Err(payload) = catch_unwind(AssertUnwindSafe(|| f(&mut scratch))) {
+                    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                        s
+                    } else if let Some(s) = payload.downcast_ref::<String>() {
+                        &s[..]
+                    } else {
+                        "Box<dyn Any>"
+                    };
+                    let error =
+                        scratch.render_error(alloc::format!("panicked: {}", message).into());
+                    scratch.error.render.push(error);
+                }
+            }
+            #[cfg(not(feature = "std"))]
+            f(&mut scratch);
+            let script_lt = scratch.script_lt;
+            let nesting = scratch.nesting.take();
+            let layout = match scratch.writer {
+                InnerTemplateWriter::Pretty(ref mut writer) => {
+                    Some(core::mem::take(&mut writer.layout))
+                }
+                _ => None,
+            };
+            scratch.into_result().map(|()| (script_lt, nesting, layout))
+        };
+        result.map(|(script_lt, nesting, layout)| {
+            self.script_lt = script_lt;
+            self.nesting = nesting;
+            if let InnerTemplateWriter::Pretty(ref mut writer) = self.writer {
+                if let Some(layout) = layout {
+                    writer.layout = layout;
+                }
+            }
+            output
+        })
+    }
+
     /// Write a raw string to the template output.
     // NEVER REMOVE THIS INLINE!
     #[inline(always)]
@@ -164,6 +601,37 @@ impl<'a> TemplateBuffer<'a> {
         let _ = self.as_writer().write_str(text);
     }
 
+    /// Returns the escaping rules currently applied to escaped writes.
+    #[inline]
+    pub fn escaping(&self) -> Escaping {
+        self.escaping
+    }
+
+    /// Change the escaping rules applied to escaped writes, returning the previous rules.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # #[macro_use] extern crate horrorshow;
+    /// # use horrorshow::prelude::*;
+    /// # use horrorshow::Escaping;
+    /// # fn main() {
+    /// let result = html! {
+    ///     |tmpl| {
+    ///         let prev = tmpl.set_escaping(Escaping::Script);
+    ///         tmpl.write_str("</script>");
+    ///         tmpl.set_escaping(prev);
+    ///     }
+    /// };
+    /// assert_eq!(result.into_string().unwrap(), "<\\/script>");
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_escaping(&mut self, escaping: Escaping) -> Escaping {
+        self.script_lt = false;
+        core::mem::replace(&mut self.escaping, escaping)
+    }
+
     /// Returns an escaping Write adapter.
     #[inline]
     pub fn as_writer<'b>(&'b mut self) -> TemplateWriter<'a, 'b> {
@@ -176,6 +644,91 @@ impl<'a> TemplateBuffer<'a> {
         RawTemplateWriter(self)
     }
 
+    /// Start tracking namespace declarations. Returns false if they're already being tracked.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn start_namespace_checks(&mut self) -> bool {
+        if self.namespaces.is_some() {
+            return false;
+        }
+        self.namespaces = Some(Box::new(NamespaceChecker::new()));
+        true
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn stop_namespace_checks(&mut self) {
+        self.namespaces = None;
+    }
+
+    /// Start tracking open HTML elements. Returns false if they're already being tracked.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn start_nesting_checks(&mut self) -> bool {
+        if self.nesting.is_some() {
+            return false;
+        }
+        self.nesting = Some(Box::new(NestingChecker::new()));
+        true
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn stop_nesting_checks(&mut self) {
+        self.nesting = None;
+    }
+
+    /// Record the error returned by the nesting checker (unless output has already stopped: the
+    /// rest of the document is likely to be wrong too).
+    #[cold]
+    #[cfg(all(feature = "alloc", debug_assertions))]
+    fn check_nesting<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut NestingChecker) -> Option<String>,
+    {
+        let error = match self.nesting {
+            Some(ref mut nesting) => f(nesting),
+            None => None,
+        };
+        if let Some(e) = error {
+            if error::is_empty(&self.error) {
+                self.record_error(e);
+            }
+        }
+    }
+
+    /// Returns true if raw output needs to be passed to `check_markup`.
+    #[inline(always)]
+    #[cfg(feature = "alloc")]
+    fn inspects_markup(&self) -> bool {
+        self.namespaces.is_some()
+            || (cfg!(debug_assertions)
+                && match self.nesting {
+                    Some(ref nesting) => nesting.is_capturing(),
+                    None => false,
+                })
+    }
+
+    /// Feed raw output to the namespace checker (and the nesting checker when it's waiting for an
+    /// attribute value). Returns false (after recording an error) if the output uses an undeclared
+    /// prefix.
+    #[cold]
+    #[cfg(feature = "alloc")]
+    fn check_markup(&mut self, text: &str) -> bool {
+        if let Some(ref mut nesting) = self.nesting {
+            if nesting.is_capturing() {
+                nesting.capture(text);
+            }
+        }
+        let error = match self.namespaces {
+            Some(ref mut namespaces) => namespaces.write(text),
+            None => None,
+        };
+        match error {
+            Some(e) => {
+                self.record_error(e);
+                false
+            }
+            None => true,
+        }
+    }
+
     fn into_result(self) -> Result<(), Error> {
         if error::is_empty(&self.error) {
             Ok(())
@@ -193,6 +746,8 @@ fn new_fmt_err() -> fmt::Error {
 
 #[cfg(feature = "std")]
 #[inline(always)]
+// `io::Error::other` is newer than our MSRV.
+#[allow(unknown_lints, clippy::io_other_error)]
 fn new_fmt_err() -> io::Error {
     io::Error::new(io::ErrorKind::Other, "Format Error")
 }
@@ -211,6 +766,12 @@ impl<'a, 'b> fmt::Write for RawTemplateWriter<'a, 'b> {
         if !error::is_empty(&self.0.error) {
             return Ok(());
         }
+        #[cfg(feature = "alloc")]
+        {
+            if self.0.inspects_markup() && !self.0.check_markup(text) {
+                return Ok(());
+            }
+        }
         match self.0.writer {
             Fmt(ref mut writer) => {
                 if writer.write_str(text).is_err() {
@@ -221,6 +782,12 @@ impl<'a, 'b> fmt::Write for RawTemplateWriter<'a, 'b> {
             Str(ref mut writer) => {
                 let _ = writer.write_str(text);
             }
+            #[cfg(feature = "alloc")]
+            Bytes(ref mut writer) => {
+                writer.extend_from_slice(text.as_bytes());
+            }
+            #[cfg(feature = "alloc")]
+            Pretty(ref mut writer) => writer.write(text),
             #[cfg(feature = "std")]
             Io(ref mut writer) => {
                 self.0.error.write = writer.write_all(text.as_bytes()).err();
@@ -247,6 +814,13 @@ impl<'a, 'b> fmt::Write for TemplateWriter<'a, 'b> {
             return Ok(());
         }
 
+        match self.0.escaping {
+            Escaping::Html => (),
+            Escaping::HtmlAttribute => return self.write_replacing(text, escape_html_attribute),
+            Escaping::Xml => return self.write_replacing(text, escape_xml),
+            Escaping::Script | Escaping::Style => return self.write_script(text),
+        }
+
         fn should_escape(b: u8) -> bool {
             (b | 0x4) == b'&' || (b | 0x2) == b'>'
         }
@@ -268,8 +842,13 @@ impl<'a, 'b> fmt::Write for TemplateWriter<'a, 'b> {
                     }
                 }
             }
+            // Escaped text never contains tags.
             #[cfg(feature = "alloc")]
-            Str(ref mut writer) => {
+            Str(ref mut writer)
+            | Pretty(PrettyWriter {
+                out: ref mut writer,
+                ..
+            }) => {
                 for b in text.bytes() {
                     match (should_escape(b), b) {
                         (true, b'&') => writer.push_str("&amp;"),
@@ -281,6 +860,18 @@ impl<'a, 'b> fmt::Write for TemplateWriter<'a, 'b> {
                     }
                 }
             }
+            #[cfg(feature = "alloc")]
+            Bytes(ref mut writer) => {
+                for b in text.bytes() {
+                    match (should_escape(b), b) {
+                        (true, b'&') => writer.extend_from_slice(b"&amp;"),
+                        (true, b'"') => writer.extend_from_slice(b"&quot;"),
+                        (true, b'<') => writer.extend_from_slice(b"&lt;"),
+                        (true, b'>') => writer.extend_from_slice(b"&gt;"),
+                        _ => writer.push(b),
+                    }
+                }
+            }
             #[cfg(feature = "std")]
             Io(ref mut writer) => {
                 for b in text.bytes() {
@@ -300,3 +891,79 @@ impl<'a, 'b> fmt::Write for TemplateWriter<'a, 'b> {
         Ok(())
     }
 }
+
+fn escape_html_attribute(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        _ => None,
+    }
+}
+
+fn escape_xml(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&apos;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '\t' | '\n' | '\r' => None,
+        '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => Some("\u{fffd}"),
+        _ => None,
+    }
+}
+
+impl<'a, 'b> TemplateWriter<'a, 'b> {
+    /// Write the text, replacing every character for which `escape` returns a replacement.
+    fn write_replacing(
+        &mut self,
+        text: &str,
+        escape: fn(char) -> Option<&'static str>,
+    ) -> fmt::Result {
+        use fmt::Write;
+        let mut raw = RawTemplateWriter(&mut *self.0);
+        let mut start = 0;
+        for (i, &b) in text.as_bytes().iter().enumerate() {
+            // Only ASCII punctuation and control characters, and U+FFFE/U+FFFF (which start with
+            // 0xEF), are ever replaced. Neither can be in the middle of a character.
+            if b >= 0x40 && b != 0xEF {
+                continue;
+            }
+            let c = text[i..].chars().next().unwrap_or('\0');
+            if let Some(replacement) = escape(c) {
+                raw.write_str(&text[start..i])?;
+                raw.write_str(replacement)?;
+                start = i + c.len_utf8();
+            }
+        }
+        raw.write_str(&text[start..])
+    }
+
+    /// Write the text verbatim except for `</` and `<!`, which are broken up with a backslash.
+    /// These may be split across writes so we remember whether the last write ended in a `<`.
+    fn write_script(&mut self, text: &str) -> fmt::Result {
+        use fmt::Write;
+        if text.is_empty() {
+            return Ok(());
+        }
+        let after_lt = core::mem::replace(&mut self.0.script_lt, text.ends_with('<'));
+        let mut raw = RawTemplateWriter(&mut *self.0);
+        if after_lt && (text.starts_with('/') || text.starts_with('!')) {
+            raw.write_str("\\")?;
+        }
+        let mut start = 0;
+        for (i, _) in text.match_indices('<') {
+            match text.as_bytes().get(i + 1) {
+                Some(b'/') | Some(b'!') => (),
+                _ => continue,
+            }
+            raw.write_str(&text[start..=i])?;
+            raw.write_str("\\")?;
+            start = i + 1;
+        }
+        raw.write_str(&text[start..])
+    }
+}
@@ -0,0 +1,118 @@
+use core::fmt;
+
+use crate::template::TemplateBuffer;
+
+/// URL schemes that are allowed in URL attributes. Relative URLs are always allowed.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "ftp", "mailto", "tel"];
+
+/// Length of the longest scheme in `SAFE_SCHEMES`.
+const MAX_SCHEME_LEN: usize = 6;
+
+enum State {
+    /// Still reading what may be the URL's scheme (lowercased).
+    Scheme {
+        buf: [u8; MAX_SCHEME_LEN],
+        len: usize,
+        overflow: bool,
+    },
+    /// Past the scheme (or there was no scheme).
+    Rest,
+}
+
+/// Write adapter that checks the scheme of the URL written through it and percent-encodes
+/// characters that aren't allowed to appear in URLs.
+///
+/// It expects its input to already be HTML escaped; this adapter doesn't touch `&` or `%`.
+pub(crate) struct UrlWriter<'a, 'b> {
+    tmpl: &'b mut TemplateBuffer<'a>,
+    state: State,
+}
+
+impl<'a, 'b> UrlWriter<'a, 'b> {
+    pub(crate) fn new(tmpl: &'b mut TemplateBuffer<'a>) -> Self {
+        UrlWriter {
+            tmpl: tmpl,
+            state: State::Scheme {
+                buf: [0; MAX_SCHEME_LEN],
+                len: 0,
+                overflow: false,
+            },
+        }
+    }
+
+    #[inline]
+    fn write_ascii(&mut self, bytes: &[u8]) {
+        debug_assert!(bytes.is_ascii());
+        self.tmpl
+            .write_raw(unsafe { core::str::from_utf8_unchecked(bytes) });
+    }
+
+    /// Update the scheme state with the next byte (the first byte of `rest`). Returns false if the
+    /// byte should be dropped.
+    fn check_scheme(&mut self, rest: &[u8]) -> bool {
+        let b = rest[0];
+        let (buf, len, overflow) = match self.state {
+            State::Scheme {
+                ref mut buf,
+                ref mut len,
+                ref mut overflow,
+            } => (buf, len, overflow),
+            State::Rest => return true,
+        };
+        // Browsers ignore ASCII whitespace before the URL and tabs/newlines anywhere in the
+        // scheme.
+        if b == b'\t' || b == b'\n' || b == b'\r' || (*len == 0 && b <= b' ') {
+            return false;
+        }
+        match b {
+            b':' => {
+                let scheme = &buf[..*len];
+                if *overflow || !SAFE_SCHEMES.iter().any(|s| s.as_bytes() == scheme) {
+                    self.tmpl
+                        .record_error("refusing to render a URL with an unsafe scheme");
+                }
+                self.state = State::Rest;
+            }
+            b'/' | b'?' | b'#' => self.state = State::Rest,
+            // Escaped text always contains `&amp;`, which can't be part of a scheme. Anything else
+            // is a character reference (from raw text) that could be hiding a `:`.
+            b'&' if rest.starts_with(b"&amp;") => self.state = State::Rest,
+            b'&' => {
+                self.tmpl.record_error(
+                    "refusing to render a URL with a character reference in its scheme",
+                );
+                self.state = State::Rest;
+            }
+            _ if *len < MAX_SCHEME_LEN => {
+                buf[*len] = b.to_ascii_lowercase();
+                *len += 1;
+            }
+            _ => *overflow = true,
+        }
+        true
+    }
+}
+
+impl<'a, 'b> fmt::Write for UrlWriter<'a, 'b> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        // Every non-ascii byte is encoded so we only ever split the input between ascii bytes.
+        let bytes = text.as_bytes();
+        let mut start = 0;
+        for (i, &b) in bytes.iter().enumerate() {
+            let keep = self.check_scheme(&bytes[i..]);
+            match b {
+                _ if !keep => (),
+                0..=b' ' | 0x7f..=0xff | b'"' | b'\'' | b'<' | b'>' | b'`' => (),
+                _ => continue,
+            }
+            self.write_ascii(&bytes[start..i]);
+            if keep {
+                self.write_ascii(&[b'%', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]]);
+            }
+            start = i + 1;
+        }
+        self.write_ascii(&bytes[start..]);
+        Ok(())
+    }
+}
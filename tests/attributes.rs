@@ -6,6 +6,7 @@ extern crate horrorshow;
 use horrorshow::Template;
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_dash() {
     assert_eq!(
         html! {
@@ -20,6 +21,7 @@ fn test_dash() {
 }
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_no_value() {
     assert_eq!(
         html! {
@@ -34,6 +36,7 @@ fn test_no_value() {
 }
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_boolean() {
     assert_eq!(
         html! {
@@ -55,6 +58,7 @@ fn test_boolean() {
 }
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_option() {
     assert_eq!(
         html! {
@@ -74,3 +78,59 @@ fn test_option() {
         "<tag></tag>"
     );
 }
+
+#[test]
+fn test_spread() {
+    use horrorshow::Attrs;
+
+    let mut extra = Attrs::new();
+    extra
+        .insert("class", "a<b")
+        .insert("data-id", String::from("1"))
+        .insert_optional("hidden", true)
+        .insert_optional("title", Some("t"))
+        .insert_optional("checked", false)
+        .insert_optional("alt", None::<&str>)
+        .insert("class", "replaced");
+    assert_eq!(extra.len(), 4);
+    assert_eq!(
+        html! {
+            div(id = "x", ..&extra, lang = "en") : "a";
+            span(..&extra);
+            p(..Attrs::new()) : "b";
+        }
+        .into_string()
+        .unwrap(),
+        "<div id=\"x\" class=\"replaced\" data-id=\"1\" hidden title=\"t\" lang=\"en\">a</div>\
+         <span class=\"replaced\" data-id=\"1\" hidden title=\"t\"></span>\
+         <p>b</p>"
+    );
+}
+
+#[test]
+fn test_spread_escaping() {
+    use horrorshow::Attrs;
+
+    let attrs: Attrs = vec![("title", "\"'&"), ("href", "/a b")]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        html! { a(..attrs) }.into_string().unwrap(),
+        "<a title=\"&quot;&#39;&amp;\" href=\"/a%20b\"></a>"
+    );
+
+    let mut bad = Attrs::new();
+    bad.insert("href", "javascript:alert(1)");
+    assert!(html! { a(..&bad) }.into_string().is_err());
+
+    let mut bad = Attrs::new();
+    bad.insert("onclick=\"x\"", "y");
+    assert!(html! { a(..&bad) }.into_string().is_err());
+
+    let mut flags = Attrs::new();
+    flags.insert_optional("flag", true).insert("a", "'<");
+    assert_eq!(
+        xml! { item(..flags) }.into_string().unwrap(),
+        "<item flag=\"flag\" a=\"&apos;&lt;\"/>"
+    );
+}
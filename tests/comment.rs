@@ -0,0 +1,52 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::{Comment, Template};
+
+#[test]
+fn test_comment() {
+    let user = "--><script>alert(1)</script><!--";
+    assert_eq!(
+        html! {
+            @ comment : "generated";
+            div {
+                @ comment : format!("user: {}", user);
+            }
+            @ comment : user
+        }
+        .into_string()
+        .unwrap(),
+        "<!-- generated --><div><!-- user: - -><script>alert(1)</script><!- - --></div>\
+         <!-- - -><script>alert(1)</script><!- - -->"
+    );
+}
+
+#[test]
+fn test_comment_dashes() {
+    for &(input, expected) in &[
+        ("", "<!--  -->"),
+        ("-", "<!-- - -->"),
+        ("--", "<!-- - - -->"),
+        ("a---b", "<!-- a- - -b -->"),
+        ("--!>", "<!-- - -!> -->"),
+        ("é--é", "<!-- é- -é -->"),
+    ] {
+        assert_eq!(html! { : Comment(input) }.into_string().unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_xml_comment() {
+    assert_eq!(
+        xml! {
+            root {
+                @ comment : "a -- b";
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<root><!-- a - - b --></root>"
+    );
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::dialect::{Dialect, Html, Xhtml, Xml};
+use horrorshow::{Attrs, Escaping, Template};
+
+/// HTML with a few obsolete void elements.
+struct LegacyHtml;
+
+impl Dialect for LegacyHtml {
+    fn is_void(tag: &str) -> bool {
+        Html::is_void(tag) || tag == "keygen" || tag == "frame"
+    }
+}
+
+/// An XML-like dialect that keeps the default (HTML) handling of `script` contents.
+struct Svg;
+
+impl Dialect for Svg {
+    const ESCAPING: Escaping = Escaping::Xml;
+    const ATTRIBUTE_ESCAPING: Escaping = Escaping::Xml;
+    const BOOLEAN_ATTRIBUTE_VALUES: bool = true;
+
+    fn is_void(_tag: &str) -> bool {
+        false
+    }
+
+    fn empty_tag_end(_tag: &str) -> Option<&'static str> {
+        Some("/>")
+    }
+
+    fn is_url_attribute(name: &str) -> bool {
+        name == "xlink:href"
+    }
+}
+
+#[test]
+fn test_custom_void_elements() {
+    let frame = "frame";
+    assert_eq!(
+        markup!(LegacyHtml;
+            frameset {
+                frame(src = "a b.html");
+                frame;
+                "keygen"(name = "k");
+                br;
+                div;
+                @ tag(frame);
+                input(checked);
+            }
+        )
+        .into_string()
+        .unwrap(),
+        "<frameset><frame src=\"a%20b.html\"><frame><keygen name=\"k\"><br><div></div>\
+         <frame><input checked></frameset>"
+    );
+    // Not void in plain HTML.
+    assert_eq!(html! { "frame"; }.into_string().unwrap(), "<frame></frame>");
+}
+
+#[test]
+fn test_custom_escaping() {
+    let mut attrs = Attrs::new();
+    attrs
+        .insert("xlink:href", "a b")
+        .insert_optional("hidden", true);
+    assert_eq!(
+        owned_markup!(Svg;
+            svg(width = "'1'", visible) {
+                use(xlink:href = "#a b", ..attrs);
+                script : "a < b";
+                text : "'";
+            }
+        )
+        .into_string()
+        .unwrap(),
+        "<svg width=\"&apos;1&apos;\" visible=\"visible\">\
+         <use xlink:href=\"#a%20b\" xlink:href=\"a%20b\" hidden=\"hidden\"/>\
+         <script>a < b</script><text>&apos;</text></svg>"
+    );
+}
+
+#[test]
+fn test_builtin_dialects_match_macros() {
+    for tag in &["area", "br", "img", "wbr", "div", "frame", "keygen"] {
+        let html = html! { @ tag(tag); }.into_string().unwrap();
+        assert_eq!(Html::is_void(tag), !html.contains("</"), "{}", tag);
+        assert!(!Xml::is_void(tag));
+        assert_eq!(Xhtml::is_void(tag), Html::is_void(tag));
+    }
+    assert_eq!(
+        html! { area; base; br; col; embed; hr; img; input; link; meta; param; source; track; wbr; }
+            .into_string()
+            .unwrap(),
+        "<area><base><br><col><embed><hr><img><input><link><meta><param><source><track><wbr>"
+    );
+    assert_eq!(
+        markup!(Html; area; br; div; script : "a < b"; a(href = "x y", hidden);)
+            .into_string()
+            .unwrap(),
+        html! { area; br; div; script : "a < b"; a(href = "x y", hidden); }
+            .into_string()
+            .unwrap()
+    );
+    assert_eq!(
+        markup!(Xhtml; br; div; script : "a < b"; a(href = "x y", hidden);)
+            .into_string()
+            .unwrap(),
+        xhtml! { br; div; script : "a < b"; a(href = "x y", hidden); }
+            .into_string()
+            .unwrap()
+    );
+    assert_eq!(
+        markup!(Xml; br; a(href = "x y", hidden);)
+            .into_string()
+            .unwrap(),
+        xml! { br; a(href = "x y", hidden); }.into_string().unwrap()
+    );
+}
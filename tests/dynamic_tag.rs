@@ -0,0 +1,110 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::Template;
+
+#[test]
+fn test_dynamic_tag() {
+    let level = 2;
+    assert_eq!(
+        html! {
+            @ tag(format!("h{}", level))(class = "title") : "Heading";
+            @ tag("section") {
+                @ tag("p") : "text";
+            }
+            @ tag("div");
+            @ tag("span")(id = "x");
+            @ tag(String::from("em"))
+        }
+        .into_string()
+        .unwrap(),
+        "<h2 class=\"title\">Heading</h2>\
+         <section><p>text</p></section>\
+         <div></div><span id=\"x\"></span><em></em>"
+    );
+}
+
+#[test]
+fn test_dynamic_void() {
+    let names = ["br", "img", "Hr"];
+    assert_eq!(
+        html! {
+            @ for name in &names {
+                @ tag(name)(alt = "a");
+            }
+            @ tag("input") {}
+        }
+        .into_string()
+        .unwrap(),
+        "<br alt=\"a\"><img alt=\"a\"><Hr alt=\"a\"><input>"
+    );
+    assert_eq!(
+        xml! {
+            @ tag("br");
+            @ tag("item")(id = "1") : "a";
+        }
+        .into_string()
+        .unwrap(),
+        "<br/><item id=\"1\">a</item>"
+    );
+    assert_eq!(
+        xhtml! {
+            @ tag("br") {}
+            @ tag("div") {}
+        }
+        .into_string()
+        .unwrap(),
+        "<br/><div></div>"
+    );
+}
+
+#[test]
+fn test_dynamic_void_children() {
+    let e = html! { @ tag("br") { : "x" } }.into_string().unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(e.render[0].to_string(), "void elements can't have children");
+    let mut s = String::new();
+    assert!(xhtml! { p { @ tag("IMG")(alt = "a") { : "x" } } }
+        .write_to_string(&mut s)
+        .is_err());
+    assert_eq!(s, "<p><IMG alt=\"a\"");
+    // XML doesn't have void elements.
+    assert_eq!(
+        xml! { @ tag("br") : "x" }.into_string().unwrap(),
+        "<br>x</br>"
+    );
+}
+
+#[test]
+fn test_dynamic_script() {
+    assert_eq!(
+        html! {
+            @ tag("script") : "</script>";
+            @ tag("p") : "</p>";
+        }
+        .into_string()
+        .unwrap(),
+        "<script><\\/script></script><p>&lt;/p&gt;</p>"
+    );
+}
+
+#[test]
+fn test_invalid_dynamic_tag() {
+    for name in &["", "1h", "a b", "a>", "x\"", "<script"] {
+        let e = html! {
+            @ tag(name) : "text";
+        }
+        .into_string()
+        .unwrap_err();
+        assert_eq!(e.render.len(), 1);
+        assert_eq!(&e.render[0].to_string(), "invalid tag name");
+    }
+    assert_eq!(
+        html! { @ tag("my-element.x_1:ÿ") {} }
+            .into_string()
+            .unwrap(),
+        "<my-element.x_1:ÿ></my-element.x_1:ÿ>"
+    );
+}
@@ -25,6 +25,7 @@ fn test_result() {
 }
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_record() {
     let e = (html! {
         tag {
@@ -38,3 +39,365 @@ fn test_record() {
     assert_eq!(e.render.len(), 1);
     assert_eq!(&e.render[0].to_string(), "test");
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn test_fail_fast() {
+    use horrorshow::FailFast;
+    use std::cell::Cell;
+
+    let evaluated = Cell::new(0);
+    let expensive = |s: &'static str| {
+        evaluated.set(evaluated.get() + 1);
+        s
+    };
+    let sub = html! { : expensive("sub") };
+    let mut s = String::new();
+    let e = FailFast(html! {
+        : expensive("a");
+        @ for i in 0..10 {
+            p {
+                : i;
+                : if i == 1 { Err("fail") } else { Ok(expensive("b")) };
+            }
+        }
+        |_tmpl| { expensive("closure"); }
+        : &sub;
+        : Err::<&str, &str>("fail2");
+    })
+    .write_to_string(&mut s)
+    .err()
+    .unwrap();
+    assert_eq!(s, "a<p>0b</p><p>1");
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(&e.render[0].to_string(), "fail");
+    assert_eq!(evaluated.get(), 2);
+
+    // Without FailFast, everything is evaluated.
+    evaluated.set(0);
+    let e = html! {
+        @ for i in 0..3 {
+            : if i == 0 { Err("fail") } else { Ok(expensive("b")) };
+        }
+        : &sub;
+    }
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(evaluated.get(), 3);
+}
+
+#[test]
+fn test_fail_fast_while() {
+    use horrorshow::FailFast;
+
+    // The loop condition never changes once the body stops running.
+    let mut calls = 0;
+    let e = FailFast(html! {
+        @ while calls < 5 {
+            |tmpl| {
+                calls += 1;
+                tmpl.record_error("x");
+            }
+        }
+    })
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(calls, 1);
+
+    let mut iter = 0..5;
+    let mut seen = Vec::new();
+    let e = FailFast(html! {
+        @ while let Some(i) = iter.next() {
+            |tmpl| {
+                seen.push(i);
+                tmpl.record_error("y");
+            }
+        }
+    })
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(seen, [0]);
+}
+
+#[test]
+fn test_fail_fast_section() {
+    use horrorshow::{Escaping, FailFast};
+
+    // Aborting a `FailFast` section must not leave its elements, template or escaping behind.
+    let mut escaping = None;
+    let line = line!() + 1;
+    let e = html! {
+        div {
+            : FailFast(xml! {
+                section {
+                    : Err::<&str, &str>("first");
+                    : "more";
+                    @ for i in 0..3 { : i }
+                }
+            });
+        }
+        p {
+            |tmpl| escaping = Some(tmpl.escaping());
+            : Err::<&str, &str>("second");
+        }
+    }
+    .into_string()
+    .unwrap_err();
+    assert_eq!(escaping, Some(Escaping::Html));
+    assert_eq!(e.render.len(), 2);
+    assert_eq!(e.render[0].path, ["div", "section"]);
+    assert_eq!(e.render[1].to_string(), "second");
+    assert_eq!(e.render[1].path, ["p"]);
+    assert_eq!(e.render[1].location.unwrap().line, line);
+}
+
+#[test]
+fn test_error_path_siblings() {
+    let fail = |s: &'static str| Err::<&str, &str>(s);
+    let e = html! {
+        div {
+            p { : fail("a") }
+            span(id = "x") {
+                : fail("b");
+                em { : fail("c") }
+                br;
+            }
+        }
+        : fail("d");
+        @ tag("section") { @ tag("h1") : fail("e") }
+    }
+    .into_string()
+    .unwrap_err();
+    let paths: Vec<_> = e.render.iter().map(|e| e.path.join(" > ")).collect();
+    assert_eq!(
+        paths,
+        [
+            "div > p",
+            "div > span",
+            "div > span > em",
+            "",
+            "section > h1"
+        ]
+    );
+}
+
+#[test]
+fn test_error_path() {
+    let line = line!() + 1;
+    let sub = html! {
+        span(class = "sub") {
+            |tmpl| tmpl.record_error("inner");
+        }
+    };
+    let tag = "section";
+    let e = html! {
+        html {
+            body {
+                div(id = "a") { : &sub; }
+                @ tag(tag) {
+                    img(src = "javascript:x");
+                }
+                p;
+                |tmpl| tmpl.record_error("outer");
+            }
+        }
+    }
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 3);
+
+    let inner = &e.render[0];
+    assert_eq!(inner.to_string(), "inner");
+    assert_eq!(inner.path, ["html", "body", "div", "span"]);
+    let location = inner.location.unwrap();
+    assert_eq!((location.file, location.line), (file!(), line));
+    assert_eq!(
+        format!("{:#}", inner),
+        format!(
+            "inner (at html > body > div > span, in template at {}:{})",
+            file!(),
+            line
+        )
+    );
+
+    assert_eq!(e.render[1].path, ["html", "body", "section", "img"]);
+    assert_eq!(e.render[2].to_string(), "outer");
+    assert_eq!(e.render[2].path, ["html", "body"]);
+    assert_eq!(e.render[2].location.unwrap().line, line + 6);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_error_trait() {
+    use std::error::Error as _;
+    use std::io;
+
+    let line = line!() + 1;
+    let e = html! {
+        div {
+            |tmpl| tmpl.record_error("first");
+            |tmpl| tmpl.record_error("x".parse::<u8>().unwrap_err());
+        }
+    }
+    .into_string()
+    .unwrap_err();
+    assert!(!e.is_write());
+    assert_eq!(e.render_errors().len(), 2);
+    // `Display` summarizes: the errors themselves are the source (so reporters that print the
+    // whole chain don't repeat them).
+    assert_eq!(e.to_string(), "2 render errors");
+    let source = e.source().unwrap();
+    assert_eq!(source.to_string(), "first");
+    assert_eq!(
+        source
+            .downcast_ref::<horrorshow::RenderError>()
+            .unwrap()
+            .path,
+        ["div"]
+    );
+    let causes: Vec<_> = e.causes().map(|e| e.to_string()).collect();
+    assert_eq!(causes, ["first", "invalid digit found in string"]);
+    assert_eq!(
+        format!("{:#}", e),
+        format!(
+            "2 render errors: first (at div, in template at {file}:{line}), \
+             invalid digit found in string (at div, in template at {file}:{line})",
+            file = file!(),
+            line = line
+        )
+    );
+
+    let e = html! { : Err::<&str, &str>("only") }
+        .into_string()
+        .unwrap_err();
+    assert_eq!(e.to_string(), "render error");
+    assert!(format!("{:#}", e).starts_with("render error: only (at top level, "));
+
+    #[derive(Debug)]
+    struct Wrapper(std::num::ParseIntError);
+    impl std::fmt::Display for Wrapper {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "bad number")
+        }
+    }
+    impl std::error::Error for Wrapper {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+    let e = html! {
+        |tmpl| tmpl.record_error(Wrapper("x".parse::<u8>().unwrap_err()));
+    }
+    .into_string()
+    .unwrap_err();
+    let mut chain = Vec::new();
+    let mut next: Option<&dyn std::error::Error> = Some(&e);
+    while let Some(err) = next {
+        chain.push(err.to_string());
+        next = err.source();
+    }
+    assert_eq!(
+        chain,
+        [
+            "render error",
+            "bad number",
+            "invalid digit found in string"
+        ]
+    );
+
+    let e = horrorshow::Error::from(io::Error::from(io::ErrorKind::WriteZero));
+    assert!(e.is_write());
+    assert_eq!(e.to_string(), "write error");
+    assert_eq!(format!("{:#}", e), "write error: write zero");
+    let source = e.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::WriteZero);
+    assert_eq!(e.causes().count(), 1);
+
+    assert_eq!(
+        horrorshow::Error::default().to_string(),
+        "unspecified error"
+    );
+}
+
+#[test]
+fn test_error_boundary() {
+    use horrorshow::ErrorBoundary;
+
+    let fallback = |_| html! { : "fallback" };
+    // Successful content is written as-is (and escaped as usual).
+    assert_eq!(
+        html! { p : ErrorBoundary::new(html! { b : "a < b" }, fallback) }
+            .into_string()
+            .unwrap(),
+        "<p><b>a &lt; b</b></p>"
+    );
+
+    // Failed content is discarded and the errors don't propagate.
+    let mut handled = Vec::new();
+    let result = html! {
+        div {
+            : ErrorBoundary::new(html! { span { : "partial"; |tmpl| tmpl.record_error("broken"); } }, |e| {
+                handled.extend(e.render.into_iter().map(|e| e.path.join(" > ")));
+                html! { em : "unavailable" }
+            });
+            : "after";
+        }
+    }
+    .into_string()
+    .unwrap();
+    assert_eq!(result, "<div><em>unavailable</em>after</div>");
+    // Paths start at the boundary.
+    assert_eq!(handled, ["span"]);
+
+    // Errors outside the boundary (including those recorded by the fallback) still propagate.
+    let e = html! {
+        : ErrorBoundary::new(html! { : Err::<&str, &str>("a") }, |_| Err::<&str, &str>("b"));
+    }
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(e.render[0].to_string(), "b");
+
+    // Escaping (including a `<` at the end of the last write) is inherited.
+    assert_eq!(
+        html! { script { : "<"; : ErrorBoundary::new("/script>", fallback); } }
+            .into_string()
+            .unwrap(),
+        "<script><\\/script></script>"
+    );
+    assert_eq!(
+        html! { script : ErrorBoundary::new("</script>", fallback) }
+            .into_string()
+            .unwrap(),
+        "<script><\\/script></script>"
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_error_boundary_panic() {
+    use horrorshow::ErrorBoundary;
+
+    let fail = true;
+    let mut message = None;
+    let result = html! {
+        ul {
+            li : ErrorBoundary::new(html! { |_tmpl| if fail { panic!("oops {}", 1) } }, |e| {
+                message = Some(format!("{:#}", e.render[0]));
+                "-"
+            });
+        }
+    }
+    .into_string()
+    .unwrap();
+    assert_eq!(result, "<ul><li>-</li></ul>");
+    let message = message.unwrap();
+    assert!(
+        message.starts_with("panicked: oops 1 (at top level, in template at "),
+        "{}",
+        message
+    );
+}
@@ -3,7 +3,7 @@
 #[macro_use]
 extern crate horrorshow;
 
-use horrorshow::Escape;
+use horrorshow::{Escape, Json, Raw};
 
 #[test]
 fn test_escape() {
@@ -18,3 +18,133 @@ fn test_escape() {
         "<span>&lt;b&gt;some &amp;lt;span&amp;gt;text&amp;lt;/span&amp;gt;&lt;/b&gt;</span>"
     );
 }
+
+#[test]
+fn test_script() {
+    assert_eq!(
+        html! {
+            script : "alert(\"<hello> & goodbye\");";
+            p : "\"";
+        }
+        .to_string(),
+        "<script>alert(\"<hello> & goodbye\");</script><p>&quot;</p>"
+    );
+    assert_eq!(
+        html! {
+            script(type="text/javascript") {
+                : "var s = '</script><!-- ';";
+                : Raw("</b>");
+            }
+        }
+        .to_string(),
+        "<script type=\"text/javascript\">var s = '<\\/script><\\!-- ';</b></script>"
+    );
+}
+
+#[test]
+fn test_script_json_value() {
+    // Values interpolated with `Json` can't end the string literal (or the element).
+    let name = "'\"; alert(1); //\u{2028}</script>";
+    assert_eq!(
+        html! {
+            script {
+                : "var a = ";
+                : Json(name);
+                : ";";
+            }
+        }
+        .to_string(),
+        "<script>var a = \"'\\\"; alert(1); //\\u2028\\u003c/script\\u003e\";</script>"
+    );
+}
+
+#[test]
+fn test_script_split_writes() {
+    // `</` split across writes.
+    let (a, b) = ("<", "/script><b>");
+    assert_eq!(
+        html! { script { : a; : b; } }.to_string(),
+        "<script><\\/script><b></script>"
+    );
+    assert_eq!(
+        html! { style { : "a<"; : "!-- b"; } }.to_string(),
+        "<style>a<\\!-- b</style>"
+    );
+    // Values that write themselves in pieces.
+    assert_eq!(
+        html! { script : format_args!("{}{}{}", "<", "/script", "><img onerror=x>") }.to_string(),
+        "<script><\\/script><img onerror=x></script>"
+    );
+    // Empty writes don't reset the state.
+    assert_eq!(
+        html! { script { : "<"; : ""; : "/x" } }.to_string(),
+        "<script><\\/x</script>"
+    );
+    // A `<` outside the script doesn't affect it.
+    assert_eq!(
+        html! { p : "<"; script : "/x"; }.to_string(),
+        "<p>&lt;</p><script>/x</script>"
+    );
+}
+
+#[test]
+fn test_style() {
+    assert_eq!(
+        html! {
+            style : "a > b { content: \"</style>\" }";
+            b : "</style>";
+        }
+        .to_string(),
+        "<style>a > b { content: \"<\\/style>\" }</style><b>&lt;/style&gt;</b>"
+    );
+}
+
+#[test]
+fn test_script_xml() {
+    assert_eq!(
+        xml! {
+            script : "a < b";
+        }
+        .to_string(),
+        "<script>a &lt; b</script>"
+    );
+}
+
+#[test]
+fn test_attribute_quotes() {
+    assert_eq!(
+        html! {
+            p(title="it's \"quoted\" <&>") : "it's";
+        }
+        .to_string(),
+        "<p title=\"it&#39;s &quot;quoted&quot; &lt;&amp;&gt;\">it's</p>"
+    );
+}
+
+#[test]
+fn test_xml_escaping() {
+    assert_eq!(
+        xml! {
+            item(title="it's \"quoted\"") : "<it's & \u{1}\t\u{ffff}>";
+        }
+        .to_string(),
+        "<item title=\"it&apos;s &quot;quoted&quot;\">&lt;it&apos;s &amp; \u{fffd}\t\u{fffd}&gt;</item>"
+    );
+}
+
+#[test]
+fn test_set_escaping() {
+    use horrorshow::Escaping;
+    assert_eq!(
+        html! {
+            |tmpl| {
+                assert_eq!(tmpl.escaping(), Escaping::Html);
+                tmpl.set_escaping(Escaping::Xml);
+            }
+            : "'";
+            p(title="'") : "'";
+        }
+        .to_string(),
+        "&apos;<p title=\"&#39;\">&apos;</p>"
+    );
+}
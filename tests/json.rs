@@ -0,0 +1,87 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use std::collections::BTreeMap;
+
+use horrorshow::json::JsonValue;
+use horrorshow::{Json, Template};
+
+#[test]
+fn test_json_primitives() {
+    assert_eq!(
+        html! {
+            : Json(1u8);
+            : " ";
+            : Json(-2.5f64);
+            : " ";
+            : Json(f64::NAN);
+            : " ";
+            : Json(true);
+            : " ";
+            : Json(None::<u32>);
+        }
+        .into_string()
+        .unwrap(),
+        "1 -2.5 null true null"
+    );
+}
+
+#[test]
+fn test_json_float_exponents() {
+    assert_eq!(
+        html! {
+            : Json(&[1e300, -1e21, 1.5e-7, 1e20, 0.000001, 0.0][..]);
+            : " ";
+            : Json(3.4e38f32);
+        }
+        .into_string()
+        .unwrap(),
+        "[1e300,-1e21,1.5e-7,100000000000000000000,0.000001,0] 3.4e38"
+    );
+}
+
+#[test]
+fn test_json_string_escapes() {
+    assert_eq!(
+        html! {
+            script : Json("\"\\\n\u{1}</script><!--&\u{2028}\u{2029}")
+        }
+        .into_string()
+        .unwrap(),
+        "<script>\"\\\"\\\\\\n\\u0001\\u003c/script\\u003e\\u003c!--\\u0026\\u2028\\u2029\"</script>"
+    );
+}
+
+#[test]
+fn test_json_in_script() {
+    let mut map = BTreeMap::new();
+    map.insert("items", vec![1, 2]);
+    map.insert("none", vec![]);
+    assert_eq!(
+        html! {
+            script(type="application/json") : Json(&map);
+        }
+        .into_string()
+        .unwrap(),
+        "<script type=\"application/json\">{\"items\":[1,2],\"none\":[]}</script>"
+    );
+}
+
+#[test]
+fn test_json_value() {
+    let value = JsonValue::Object(vec![
+        ("name".into(), "<b>".into()),
+        ("tags".into(), vec!["a", "b"].into()),
+        ("missing".into(), None::<bool>.into()),
+    ]);
+    assert_eq!(
+        html! {
+            div(data-state=Json(&value));
+        }
+        .into_string()
+        .unwrap(),
+        "<div data-state=\"{&quot;name&quot;:&quot;\\u003cb\\u003e&quot;,&quot;tags&quot;:[&quot;a&quot;,&quot;b&quot;],&quot;missing&quot;:null}\"></div>"
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::Template;
+
+#[test]
+fn test_literal_attributes() {
+    assert_eq!(
+        html! {
+            button("@click" = "go()", "x-on:submit.prevent", "type" = "submit") : "Go";
+            input("data-2fa" = 1, "aria-label"? = Some("code"), "async"? = false);
+        }
+        .into_string()
+        .unwrap(),
+        "<button @click=\"go()\" x-on:submit.prevent type=\"submit\">Go</button>\
+         <input data-2fa=\"1\" aria-label=\"code\">"
+    );
+}
+
+#[test]
+fn test_literal_url_attributes() {
+    let e = html! {
+        a("href" = "javascript:alert(1)") : "x";
+    }
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(
+        html! { a("href" = "/a b") : "x"; }.into_string().unwrap(),
+        "<a href=\"/a%20b\">x</a>"
+    );
+}
+
+#[test]
+fn test_literal_tags() {
+    assert_eq!(
+        html! {
+            "my-element"("type" = "a") {
+                "br";
+                "x-y" : "text";
+            }
+            "img"(src = "a.png");
+            "script" : "</script>";
+            "type"
+        }
+        .into_string()
+        .unwrap(),
+        "<my-element type=\"a\"><br><x-y>text</x-y></my-element>\
+         <img src=\"a.png\"><script><\\/script></script><type></type>"
+    );
+    assert_eq!(
+        xml! {
+            "ns:item"("xml:lang" = "en", "flag");
+            "ns:empty";
+        }
+        .into_string()
+        .unwrap(),
+        "<ns:item xml:lang=\"en\" flag=\"flag\"/><ns:empty/>"
+    );
+}
+
+#[test]
+fn test_name_validation() {
+    use horrorshow::{is_valid_attribute_name, is_valid_tag_name};
+
+    assert!(is_valid_tag_name("h1"));
+    assert!(is_valid_tag_name("my-element"));
+    assert!(!is_valid_tag_name(""));
+    assert!(!is_valid_tag_name("-x"));
+    assert!(!is_valid_tag_name("a b"));
+    assert!(!is_valid_tag_name("a>"));
+
+    assert!(is_valid_attribute_name("@click"));
+    assert!(is_valid_attribute_name(":class"));
+    assert!(is_valid_attribute_name("x-on:submit.prevent"));
+    assert!(!is_valid_attribute_name(""));
+    assert!(!is_valid_attribute_name("a b"));
+    assert!(!is_valid_attribute_name("a=b"));
+    assert!(!is_valid_attribute_name("a\"b"));
+    assert!(!is_valid_attribute_name("a>"));
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::{CheckNamespaces, Raw, Template};
+
+#[test]
+fn test_declared() {
+    assert_eq!(
+        CheckNamespaces(xml! {
+            : horrorshow::helper::declaration::XML;
+            svg(xmlns = "http://www.w3.org/2000/svg", xmlns:xlink = "http://www.w3.org/1999/xlink") {
+                @ comment : "a:b <c:d>";
+                use(xlink:href = "#a", xml:lang = "en");
+                g {
+                    "x:rect"(xmlns:x = "urn:x", "x:width" = 1) {
+                        "x:title" : "<y:z>";
+                    }
+                }
+            }
+        })
+        .into_string()
+        .unwrap(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">\
+         <!-- a:b <c:d> -->\
+         <use xlink:href=\"#a\" xml:lang=\"en\"/>\
+         <g><x:rect xmlns:x=\"urn:x\" x:width=\"1\"><x:title>&lt;y:z&gt;</x:title></x:rect></g>\
+         </svg>"
+    );
+}
+
+#[test]
+fn test_undeclared() {
+    let e = CheckNamespaces(xml! {
+        root {
+            "a:item";
+        }
+    })
+    .into_string()
+    .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(
+        &e.render[0].to_string(),
+        "undeclared namespace prefix `a` on element `a:item`"
+    );
+
+    let e = CheckNamespaces(xml! {
+        root(xlink:href = "#a");
+    })
+    .into_string()
+    .unwrap_err();
+    assert_eq!(
+        &e.render[0].to_string(),
+        "undeclared namespace prefix `xlink` on attribute `xlink:href`"
+    );
+}
+
+#[test]
+fn test_scope() {
+    // Declarations only apply to the element and its descendants.
+    let e = CheckNamespaces(xml! {
+        root {
+            "a:one"(xmlns:a = "urn:a") { "a:two"; }
+            "a:three"(xmlns:a = "urn:a");
+            "a:four";
+        }
+    })
+    .into_string()
+    .unwrap_err();
+    assert_eq!(
+        &e.render[0].to_string(),
+        "undeclared namespace prefix `a` on element `a:four`"
+    );
+
+    // Output stops at the error.
+    let mut s = String::new();
+    let _ = CheckNamespaces(xml! {
+        root {
+            : Raw("<b:x xmlns:b='urn:b'/><![CDATA[<c:y>]]><?pi <d:z>?>");
+            "b:x";
+        }
+    })
+    .write_to_string(&mut s);
+    assert_eq!(
+        s,
+        "<root><b:x xmlns:b='urn:b'/><![CDATA[<c:y>]]><?pi <d:z>?>"
+    );
+}
+
+#[test]
+fn test_bad_declarations() {
+    for tmpl in &[
+        Box::new(xml! { root(xmlns:a = ""); }) as Box<dyn horrorshow::Render>,
+        Box::new(xml! { root(xmlns:xml = "urn:a"); }),
+        Box::new(xml! { root(xmlns:a = "http://www.w3.org/XML/1998/namespace"); }),
+        Box::new(xml! { root(xmlns:xmlns = "urn:a"); }),
+        Box::new(xml! { "xmlns:root"; }),
+    ] {
+        assert!(CheckNamespaces(&**tmpl).into_string().is_err());
+    }
+    assert!(CheckNamespaces(xml! {
+        root(xmlns:xml = "http://www.w3.org/XML/1998/namespace");
+    })
+    .into_string()
+    .is_ok());
+}
+
+#[test]
+fn test_unchecked() {
+    assert_eq!(xml! { "a:item"; }.into_string().unwrap(), "<a:item/>");
+}
@@ -0,0 +1,154 @@
+#![cfg(all(feature = "alloc", debug_assertions))]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::{Attrs, CheckNesting, ErrorBoundary, Raw, Template};
+
+fn error<T: Template>(tmpl: T) -> String {
+    let e = tmpl.into_string().unwrap_err();
+    assert_eq!(e.render.len(), 1);
+    e.render[0].to_string()
+}
+
+#[test]
+fn test_valid() {
+    assert_eq!(
+        CheckNesting(html! {
+            div {
+                p { span { em : "a" } br; a(href = "/") { img(src = "a.png"); } }
+                ul { li { div { p : "b" } } }
+                dl { dt : "c"; div { dd : "d" } }
+                table { tr { td { button(type = "submit") : "e" } } }
+                form { select { optgroup { option : "f" } } }
+                a(href = "/") { div : "g"; input(type = "hidden", name = "h"); }
+                script : "document.write('<p><div>')";
+                template { li : "i" }
+            }
+        })
+        .into_string()
+        .unwrap(),
+        "<div><p><span><em>a</em></span><br><a href=\"/\"><img src=\"a.png\"></a></p>\
+         <ul><li><div><p>b</p></div></li></ul>\
+         <dl><dt>c</dt><div><dd>d</dd></div></dl>\
+         <table><tr><td><button type=\"submit\">e</button></td></tr></table>\
+         <form><select><optgroup><option>f</option></optgroup></select></form>\
+         <a href=\"/\"><div>g</div><input type=\"hidden\" name=\"h\"></a>\
+         <script>document.write('<p><div>')</script>\
+         <template><li>i</li></template></div>"
+    );
+    // Fragments are fine.
+    assert!(CheckNesting(html! { li : "a"; td : "b" })
+        .into_string()
+        .is_ok());
+}
+
+#[test]
+fn test_invalid() {
+    assert_eq!(
+        error(CheckNesting(html! { p { div : "a" } })),
+        "`<div>` can't appear inside `<p>` (which may only contain phrasing content)"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { h1 { span { ul { li : "a" } } } })),
+        "`<ul>` can't appear inside `<span>` (which may only contain phrasing content)"
+    );
+    assert_eq!(
+        error(CheckNesting(
+            html! { a(href = "/a") { div { a(href = "/b") : "b" } } }
+        )),
+        "interactive element `<a>` can't appear inside `<a>`"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { button { input(type = "text"); } })),
+        "interactive element `<input>` can't appear inside `<button>`"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { form { div { form; } } })),
+        "`<form>` can't appear inside another `<form>`"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { div { li : "a" } })),
+        "`<li>` must be a child of `<ul>`, `<ol>` or `<menu>`, not `<div>`"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { table { tr { td { td; } } } })),
+        "`<td>` must be a child of `<tr>`, not `<td>`"
+    );
+}
+
+#[test]
+#[cfg(not(feature = "strict-html"))] // "strict-html" rejects uppercase names.
+fn test_invalid_uppercase() {
+    assert_eq!(
+        error(CheckNesting(html! { P { DIV; } })),
+        "`<div>` can't appear inside `<p>` (which may only contain phrasing content)"
+    );
+}
+
+#[test]
+fn test_dynamic() {
+    assert_eq!(
+        error(CheckNesting(html! { p { @ tag("div") : "a" } })),
+        "`<div>` can't appear inside `<p>` (which may only contain phrasing content)"
+    );
+    let mut hidden = Attrs::new();
+    hidden.insert("type", "HIDDEN");
+    let text: Attrs = vec![("type", "text")].into_iter().collect();
+    assert!(CheckNesting(html! { a { @ tag("input")(..&hidden); } })
+        .into_string()
+        .is_ok());
+    assert_eq!(
+        error(CheckNesting(html! { a { input(..&text); } })),
+        "interactive element `<input>` can't appear inside `<a>`"
+    );
+    assert_eq!(
+        error(CheckNesting(html! { a { input; } })),
+        "interactive element `<input>` can't appear inside `<a>`"
+    );
+}
+
+#[test]
+fn test_error_boundary() {
+    let mut errors = Vec::new();
+    let page = CheckNesting(html! {
+        p {
+            : ErrorBoundary::new(html! { div : "a" }, |e| {
+                errors.push(e.to_string());
+                html! { span : "b" }
+            });
+        }
+    });
+    assert_eq!(page.into_string().unwrap(), "<p><span>b</span></p>");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_raw_unchecked() {
+    // Only elements written by templates are checked.
+    assert_eq!(
+        CheckNesting(html! { p { : Raw("<div></div>") } })
+            .into_string()
+            .unwrap(),
+        "<p><div></div></p>"
+    );
+}
+
+#[test]
+fn test_stops_output() {
+    let mut s = String::new();
+    let _ = CheckNesting(html! {
+        p { : "a"; div : "b" }
+        p : "c";
+    })
+    .write_to_string(&mut s);
+    assert_eq!(s, "<p>a");
+}
+
+#[test]
+fn test_unchecked() {
+    assert_eq!(
+        html! { p { div : "a" } }.into_string().unwrap(),
+        "<p><div>a</div></p>"
+    );
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::helper::doctype;
+use horrorshow::{ErrorBoundary, Raw, Template};
+
+#[test]
+fn test_pretty_document() {
+    let page = html! {
+        : doctype::HTML;
+        html {
+            head {
+                title : "Hello <world>";
+                meta(charset="utf-8");
+            }
+            body(class="main") {
+                h1(id="title", data-x="a>b") : "Hello";
+                @ comment : "a <b> comment";
+                p {
+                    : "Some ";
+                    b : "bold";
+                    : " text.";
+                }
+                br;
+                div;
+            }
+        }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "\
+<!DOCTYPE html>
+<html>
+  <head>
+    <title>Hello &lt;world&gt;</title>
+    <meta charset=\"utf-8\">
+  </head>
+  <body class=\"main\">
+    <h1 id=\"title\" data-x=\"a&gt;b\">Hello</h1>
+    <!-- a <b> comment -->
+    <p>Some <b>bold</b> text.</p><br>
+    <div></div>
+  </body>
+</html>"
+    );
+}
+
+#[test]
+fn test_pretty_preformatted() {
+    let page = html! {
+        div {
+            pre {
+                : "  some\n   text ";
+                b : "bold";
+            }
+            textarea : "\n x";
+            script : "if (a < b) { x = '</scrip'; }";
+        }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "\
+<div>
+  <pre>  some
+   text <b>bold</b></pre>
+  <textarea>
+ x</textarea>
+  <script>if (a < b) { x = '<\\/scrip'; }</script>
+</div>"
+    );
+}
+
+#[test]
+fn test_pretty_xml() {
+    let feed = xml! {
+        rss {
+            channel {
+                item {
+                    title : "A";
+                    link : "https://example.com";
+                    guid(isPermaLink="false");
+                }
+            }
+        }
+    };
+    assert_eq!(
+        feed.into_string_pretty().unwrap(),
+        "\
+<rss>
+  <channel>
+    <item>
+      <title>A</title>
+      <link>https://example.com</link>
+      <guid isPermaLink=\"false\"/>
+    </item>
+  </channel>
+</rss>"
+    );
+}
+
+#[test]
+fn test_pretty_non_ascii_names() {
+    let page = xml! {
+        "café" { "thé" : "x" }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "<café>\n  <thé>x</thé>\n</café>"
+    );
+}
+
+#[test]
+fn test_pretty_dynamic() {
+    let page = html! {
+        ul {
+            @ tag("li") : "a";
+            : ErrorBoundary::new(html! { li { p : "b" } }, |_| html! {});
+            @ tag("li")(class = "c");
+            @ tag("hr");
+        }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "\
+<ul>
+  <li>a</li>
+  <li>
+    <p>b</p>
+  </li>
+  <li class=\"c\"></li>
+  <hr>
+</ul>"
+    );
+}
+
+#[test]
+fn test_pretty_raw() {
+    // Raw markup is written as-is.
+    let page = html! {
+        div { : Raw("<p><b>a</b></p>") }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "<div><p><b>a</b></p></div>"
+    );
+}
+
+#[test]
+fn test_pretty_inline() {
+    // No whitespace is added next to text or inside inline elements.
+    let page = html! {
+        div {
+            p { : "a"; b : "x"; : "c" }
+            : "text";
+            span { div : "d" }
+            @ tag("em") : "e";
+            @ tag("section") { p : "f" }
+        }
+    };
+    assert_eq!(
+        page.into_string_pretty().unwrap(),
+        "\
+<div>
+  <p>a<b>x</b>c</p>text<span><div>d</div></span><em>e</em>
+  <section>
+    <p>f</p>
+  </section>
+</div>"
+    );
+}
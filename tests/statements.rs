@@ -149,3 +149,200 @@ fn test_while_let() {
         "<ol><li>0</li><li>1</li></ol>"
     );
 }
+
+#[test]
+fn test_match() {
+    enum State {
+        Loading,
+        Loaded(Vec<&'static str>),
+        Failed { reason: &'static str },
+    }
+
+    let render = |state: &State| {
+        (html! {
+            div {
+                @ match *state {
+                    State::Loading => {
+                        span : "loading";
+                    }
+                    State::Loaded(ref items) if items.is_empty() => {
+                        span : "empty"
+                    },
+                    State::Loaded(ref items) => {
+                        ul {
+                            @ for item in items {
+                                li : item
+                            }
+                        }
+                    }
+                    State::Failed { reason: "" } | State::Failed { reason: "unknown" } => {
+                        span : "failed"
+                    }
+                    State::Failed { reason } => {
+                        span(class="error") : reason
+                    },
+                }
+                p : "after";
+            }
+        })
+        .into_string()
+        .unwrap()
+    };
+
+    assert_eq!(
+        render(&State::Loading),
+        "<div><span>loading</span><p>after</p></div>"
+    );
+    assert_eq!(
+        render(&State::Loaded(vec![])),
+        "<div><span>empty</span><p>after</p></div>"
+    );
+    assert_eq!(
+        render(&State::Loaded(vec!["a", "b"])),
+        "<div><ul><li>a</li><li>b</li></ul><p>after</p></div>"
+    );
+    assert_eq!(
+        render(&State::Failed { reason: "unknown" }),
+        "<div><span>failed</span><p>after</p></div>"
+    );
+    assert_eq!(
+        render(&State::Failed { reason: "timeout" }),
+        "<div><span class=\"error\">timeout</span><p>after</p></div>"
+    );
+}
+
+#[test]
+fn test_match_expression() {
+    let n = 3;
+    assert_eq!(
+        html! {
+            @ match n % 2 == 0 {
+                true => { : "even" }
+                false => { : "odd" }
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "odd"
+    );
+}
+
+#[test]
+fn test_let() {
+    let price = 1234;
+    assert_eq!(
+        html! {
+            @ let label = format!("${}.{:02}", price / 100, price % 100);
+            @ let (count, unit) = (2, "items");
+            span(title = &label) : &label;
+            p {
+                @ let mut total: u32 = count;
+                |_tmpl| total += 1;
+                : format_args!("{} {}", total, unit);
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<span title=\"$12.34\">$12.34</span><p>3 items</p>"
+    );
+}
+
+#[test]
+fn test_let_shadowing() {
+    let x = "outer";
+    assert_eq!(
+        html! {
+            div {
+                @ let x = "inner";
+                : x;
+            }
+            : x;
+        }
+        .into_string()
+        .unwrap(),
+        "<div>inner</div>outer"
+    );
+}
+
+#[test]
+fn test_for_else() {
+    fn render(items: &[&str]) -> String {
+        (html! {
+            ul {
+                @ for item in items {
+                    li : item;
+                } else {
+                    li : "No results";
+                }
+            }
+            p : "after";
+        })
+        .into_string()
+        .unwrap()
+    }
+
+    assert_eq!(render(&[]), "<ul><li>No results</li></ul><p>after</p>");
+    assert_eq!(
+        render(&["a", "b"]),
+        "<ul><li>a</li><li>b</li></ul><p>after</p>"
+    );
+}
+
+#[test]
+fn test_for_else_filtered() {
+    let items = [1, 3, 5];
+    assert_eq!(
+        html! {
+            @ for i in items.iter().filter(|i| *i % 2 == 0) {
+                : i;
+            } else {
+                : "none";
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "none"
+    );
+}
+
+#[test]
+fn test_with_loop() {
+    use horrorshow::WithLoop;
+
+    let rows = ["a", "b", "c"];
+    assert_eq!(
+        html! {
+            @ for (row, info) in rows.iter().with_loop() {
+                tr(data-first? = info.first(), data-last? = info.last()) {
+                    td : info.index();
+                    td : row;
+                    td : info.length().unwrap();
+                }
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<tr data-first><td>0</td><td>a</td><td>3</td></tr>\
+         <tr><td>1</td><td>b</td><td>3</td></tr>\
+         <tr data-last><td>2</td><td>c</td><td>3</td></tr>"
+    );
+}
+
+#[test]
+fn test_with_loop_unknown_length() {
+    use horrorshow::WithLoop;
+
+    let mut seen = Vec::new();
+    for (i, info) in (0..5).filter(|i| i % 2 == 0).with_loop() {
+        seen.push((i, info.index(), info.first(), info.last(), info.length()));
+    }
+    assert_eq!(
+        seen,
+        vec![
+            (0, 0, true, false, None),
+            (2, 1, false, false, None),
+            (4, 2, false, true, None),
+        ]
+    );
+    assert_eq!(Vec::<u8>::new().with_loop().next(), None);
+}
@@ -0,0 +1,103 @@
+#![cfg(all(feature = "alloc", feature = "strict-html"))]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::Template;
+
+#[test]
+fn test_strict_html() {
+    assert_eq!(
+        html! {
+            div(id = "a", class = "b", data-id = 1, aria-label = "c", hidden) {
+                img(src = "a.png", alt = "");
+                input(type = "checkbox", checked, http-equiv = "x");
+                my-widget(xml:lang = "en");
+                "dvi"("scr" = "typo");
+                label(for = "x") : "d";
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<div id=\"a\" class=\"b\" data-id=\"1\" aria-label=\"c\" hidden>\
+         <img src=\"a.png\" alt=\"\"><input type=\"checkbox\" checked http-equiv=\"x\">\
+         <my-widget xml:lang=\"en\"></my-widget><dvi scr=\"typo\"></dvi>\
+         <label for=\"x\">d</label></div>"
+    );
+    // Only HTML is checked.
+    assert_eq!(
+        xml! { dvi(scr = "x"); }.into_string().unwrap(),
+        "<dvi scr=\"x\"/>"
+    );
+}
+
+// The baseline tests that use made-up names, with names valid in both modes.
+
+#[test]
+fn test_strict_attributes() {
+    assert_eq!(
+        html! {
+            div {
+                input(data-test = "abcde");
+                input(type = "text", disabled, value = "1");
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<div><input data-test=\"abcde\"><input type=\"text\" disabled value=\"1\"></div>"
+    );
+    assert_eq!(
+        html! {
+            option(selected? = true);
+            option(selected? = false);
+            option(label ?= Some("value"));
+            option(label ?= None::<&'static str>);
+        }
+        .into_string()
+        .unwrap(),
+        "<option selected></option><option></option>\
+         <option label=\"value\"></option><option></option>"
+    );
+}
+
+#[test]
+fn test_strict_option() {
+    assert_eq!(
+        html! {
+            span : Some("testing");
+            span : None::<&str>;
+        }
+        .into_string()
+        .unwrap(),
+        "<span>testing</span><span></span>"
+    );
+}
+
+#[test]
+fn test_strict_void() {
+    assert_eq!(
+        html! {
+            div;
+            br;
+            input(type = "text");
+            img
+        }
+        .into_string()
+        .unwrap(),
+        "<div></div><br><input type=\"text\"><img>"
+    );
+}
+
+#[test]
+fn test_strict_record() {
+    let e = (html! {
+        div {
+            |tmpl| tmpl.record_error("test");
+        }
+    })
+    .into_string()
+    .unwrap_err();
+    assert!(e.write.is_none());
+    assert_eq!(e.render.len(), 1);
+    assert_eq!(&e.render[0].to_string(), "test");
+}
@@ -51,3 +51,28 @@ fn test_template_in_module() {
         "<p><div>42</div></p>"
     );
 }
+
+#[test]
+fn test_bytes() {
+    let page = html! {
+        p(title = "'a' & \"b\"") : "<c> & \"d\"";
+        script : "</script>";
+        : horrorshow::Raw("<raw>");
+    };
+    let expected = page.to_string();
+    assert_eq!(
+        expected,
+        "<p title=\"&#39;a&#39; &amp; &quot;b&quot;\">&lt;c&gt; &amp; &quot;d&quot;</p>\
+         <script><\\/script></script><raw>"
+    );
+    assert_eq!(page.into_bytes().unwrap(), expected.as_bytes());
+
+    let mut bytes = b"prefix:".to_vec();
+    (html! { b : "&" }).write_to_vec(&mut bytes).unwrap();
+    assert_eq!(bytes, b"prefix:<b>&amp;</b>");
+
+    let e = html! { : Err::<&str, &str>("fail") }
+        .into_bytes()
+        .unwrap_err();
+    assert_eq!(e.render.len(), 1);
+}
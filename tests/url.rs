@@ -0,0 +1,141 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::{Raw, Template, Url};
+
+#[test]
+fn test_url_encoding() {
+    assert_eq!(
+        html! {
+            a(href="/a b/\"caf\u{e9}\"?x=<1>&y='2'#`frag`");
+        }
+        .into_string()
+        .unwrap(),
+        "<a href=\"/a%20b/&quot;caf%C3%A9&quot;?x=&lt;1&gt;&amp;y=%272%27#%60frag%60\"></a>"
+    );
+}
+
+#[test]
+fn test_url_safe_schemes() {
+    assert_eq!(
+        html! {
+            a(href="https://example.com/a:b");
+            img(src="HTTP://example.com/x.png");
+            a(href="mailto:someone@example.com");
+            a(href=format_args!("tel:{}", 5551234));
+            form(action="relative/path:with:colons");
+            a(href="?q=javascript:alert(1)");
+            a(href="a&b:c");
+        }
+        .into_string()
+        .unwrap(),
+        "<a href=\"https://example.com/a:b\"></a>\
+         <img src=\"HTTP://example.com/x.png\">\
+         <a href=\"mailto:someone@example.com\"></a>\
+         <a href=\"tel:5551234\"></a>\
+         <form action=\"relative/path:with:colons\"></form>\
+         <a href=\"?q=javascript:alert(1)\"></a>\
+         <a href=\"a&amp;b:c\"></a>"
+    );
+}
+
+#[test]
+fn test_url_unsafe_schemes() {
+    let bad = [
+        "javascript:alert(1)",
+        "  JaVaScRiPt:alert(1)",
+        "java\tscript:alert(1)",
+        "data:text/html,<script>alert(1)</script>",
+        "vbscript:msgbox",
+        "foo:bar",
+    ];
+    for url in &bad {
+        let mut s = String::new();
+        let err = html! {
+            a(href=*url) : "link";
+        }
+        .write_to_string(&mut s)
+        .expect_err(url);
+        assert_eq!(err.render.len(), 1, "{}", url);
+        assert!(!s.contains(':'), "{} rendered as {}", url, s);
+    }
+
+    assert!(html! {
+        a(href = Raw("javascript&#58;alert(1)"));
+    }
+    .into_string()
+    .is_err());
+
+    assert!(html! {
+        iframe(src = Raw("javascript:alert(1)"));
+    }
+    .into_string()
+    .is_err());
+
+    assert!(html! {
+        a(href ?= Some("javascript:alert(1)"));
+    }
+    .into_string()
+    .is_err());
+
+    // Attribute names are case insensitive.
+    assert!(html! { a("HREF" = "javascript:alert(1)"); }
+        .into_string()
+        .is_err());
+    assert!(html! { img("Src" = "javascript:alert(1)"); }
+        .into_string()
+        .is_err());
+}
+
+#[test]
+#[cfg(not(feature = "strict-html"))] // "strict-html" rejects uppercase names.
+fn test_url_unsafe_schemes_uppercase() {
+    assert!(html! { a(HREF = "javascript:alert(1)"); }
+        .into_string()
+        .is_err());
+    assert!(xhtml! { a(xlink:HREF = "javascript:alert(1)"); }
+        .into_string()
+        .is_err());
+}
+
+#[test]
+fn test_url_not_url_attrs() {
+    assert_eq!(
+        html! {
+            a(title="javascript:alert(1)");
+        }
+        .into_string()
+        .unwrap(),
+        "<a title=\"javascript:alert(1)\"></a>"
+    );
+    assert_eq!(
+        html! {
+            div(data="x:y z");
+        }
+        .into_string()
+        .unwrap(),
+        "<div data=\"x:y z\"></div>"
+    );
+    assert_eq!(
+        xml! {
+            link(href="urn:isbn:0451450523");
+        }
+        .into_string()
+        .unwrap(),
+        "<link href=\"urn:isbn:0451450523\"/>"
+    );
+}
+
+#[test]
+fn test_url_renderer() {
+    assert_eq!(
+        html! {
+            : Url("/a b");
+        }
+        .into_string()
+        .unwrap(),
+        "/a%20b"
+    );
+}
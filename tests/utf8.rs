@@ -11,6 +11,9 @@ fn test_utf8() {
     // Test String
     #[cfg(feature = "alloc")]
     assert_eq!(html! {: data}.into_string().unwrap(), data);
+    // Test Vec<u8>
+    #[cfg(feature = "alloc")]
+    assert_eq!(html! {: data}.into_bytes().unwrap(), data.as_bytes());
 
     // Test io::Write (if we have std)
     #[cfg(feature = "std")]
@@ -6,6 +6,7 @@ extern crate horrorshow;
 use horrorshow::Template;
 
 #[test]
+#[cfg(not(feature = "strict-html"))] // Uses non-HTML names.
 fn test_void() {
     assert_eq!(
         html! {
@@ -19,3 +20,14 @@ fn test_void() {
         "<div></div><br><input foo=\"bar\"><img>"
     );
 }
+
+#[test]
+fn test_void_children_xml() {
+    // XML has no void elements (`html!` rejects these at compile time).
+    assert_eq!(
+        xml! { br { : "x" } img(src = "a") {} }
+            .into_string()
+            .unwrap(),
+        "<br>x</br><img src=\"a\"></img>"
+    );
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate horrorshow;
+
+use horrorshow::{Attrs, Template};
+
+#[test]
+fn test_xhtml() {
+    assert_eq!(
+        xhtml! {
+            html(xmlns = "http://www.w3.org/1999/xhtml") {
+                body {
+                    div;
+                    br;
+                    "img"(src = "a.png", alt = "it's");
+                    input(type = "checkbox", checked, disabled? = true, "required");
+                    p : "<&>";
+                    script : "if (a < b) {}";
+                }
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<html xmlns=\"http://www.w3.org/1999/xhtml\"><body>\
+         <div></div><br/><img src=\"a.png\" alt=\"it&apos;s\"/>\
+         <input type=\"checkbox\" checked=\"checked\" disabled=\"disabled\" required=\"required\"/>\
+         <p>&lt;&amp;&gt;</p><script>if (a &lt; b) {}</script>\
+         </body></html>"
+    );
+}
+
+#[test]
+fn test_xhtml_urls() {
+    assert_eq!(
+        xhtml! { a(href = "/a b") : "x" }.into_string().unwrap(),
+        "<a href=\"/a%20b\">x</a>"
+    );
+    assert!(xhtml! { a(href = "javascript:x") }.into_string().is_err());
+}
+
+#[test]
+fn test_xhtml_dynamic() {
+    let mut attrs = Attrs::new();
+    attrs
+        .insert_optional("hidden", true)
+        .insert("src", "a b.png");
+    assert_eq!(
+        xhtml! {
+            @ tag("hr");
+            @ tag("img")(..&attrs);
+            @ tag("span");
+            @ tag("script") : "<";
+        }
+        .into_string()
+        .unwrap(),
+        "<hr/><img hidden=\"hidden\" src=\"a%20b.png\"/><span></span><script>&lt;</script>"
+    );
+}
@@ -34,3 +34,56 @@ fn test_xml_bool_attr() {
         "<root><first attr=\"attr\"/><second attr=\"attr\"/><second/></root>",
     );
 }
+
+#[test]
+fn test_cdata() {
+    use horrorshow::CData;
+
+    assert_eq!(
+        xml! {
+            root {
+                @ cdata : "<b>&</b>";
+                @ cdata : format!("a]]>b]]>{}", "]]>")
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<root><![CDATA[<b>&</b>]]>\
+         <![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]]]><![CDATA[>]]></root>"
+    );
+    assert_eq!(
+        xml! { : CData("]]]>") }.into_string().unwrap(),
+        "<![CDATA[]]]]]><![CDATA[>]]>"
+    );
+}
+
+#[test]
+fn test_processing_instruction() {
+    use horrorshow::helper::declaration;
+
+    assert_eq!(
+        xml! {
+            : declaration::XML;
+            @ pi("xml-stylesheet") : "type=\"text/css\" href=\"style.css\"";
+            root {
+                @ pi("php");
+            }
+        }
+        .into_string()
+        .unwrap(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <?xml-stylesheet type=\"text/css\" href=\"style.css\"?>\
+         <root><?php?></root>"
+    );
+
+    for &(target, data) in &[
+        ("xml", ""),
+        ("XML", "a"),
+        ("a b", ""),
+        ("", ""),
+        ("ok", "?>"),
+    ] {
+        let e = xml! { @ pi(target) : data; }.into_string().unwrap_err();
+        assert_eq!(e.render.len(), 1);
+    }
+}